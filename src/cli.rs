@@ -0,0 +1,257 @@
+// Command-line module for HyprGrid
+//
+// This module parses the command-line arguments. HyprGrid is launched from
+// Hyprland keybinds, so the interface is small and parsed by hand.
+
+use crate::dump::DumpFormat;
+use crate::input::Action;
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+/// Usage text printed for `--help` and after argument errors
+pub const USAGE: &str = "\
+Usage: hyprgrid [OPTIONS] [COMMAND]
+
+Commands:
+  (none)        Show the grid overlay and act on the selected cell
+  check-config  Validate the configuration file and exit
+  dump          Print every cell of the grid as JSON or CSV, without
+                showing the overlay
+  click <LABEL> Click the center of a cell without showing the overlay
+  move <LABEL>  Move the pointer to the center of a cell without clicking
+
+Options:
+  -c, --config <PATH>    Use this configuration file instead of searching
+                         $HYPRGRID_CONFIG, $XDG_CONFIG_HOME and ~/.config
+      --rows <N>         Grid rows, overriding the configuration
+      --cols <N>         Grid columns, overriding the configuration
+  -m, --monitor <NAME>   Use this monitor instead of the focused one
+  -a, --action <ACTION>  Perform this action with every confirm key: left-click,
+                         right-click, middle-click, double-click, triple-click,
+                         shift-click, ctrl-click, alt-click, move or drag
+      --format <FORMAT>  Output format of dump: json (default) or csv
+      --button <BUTTON>  Button used by click: left (default), right or middle
+  -h, --help             Print this help
+";
+
+/// What HyprGrid should do
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Show the overlay and act on the selected cell
+    #[default]
+    Overlay,
+    /// Validate the configuration and exit
+    CheckConfig,
+    /// Print the grid without showing the overlay
+    Dump,
+    /// Click the cell with this label without showing the overlay
+    Click(String),
+    /// Move the pointer to the cell with this label without showing the overlay
+    Move(String),
+}
+
+/// Parsed command-line arguments
+///
+/// Options that mirror configuration settings are layered over the
+/// configuration file, and over its `[monitor."..."]` tables.
+#[derive(Debug, Default, PartialEq)]
+pub struct Cli {
+    /// The command to run
+    pub command: Command,
+    /// Configuration file given with `--config`
+    pub config: Option<PathBuf>,
+    /// Grid rows given with `--rows`
+    pub rows: Option<u32>,
+    /// Grid columns given with `--cols`
+    pub cols: Option<u32>,
+    /// Monitor given with `--monitor`, instead of the focused one
+    pub monitor: Option<String>,
+    /// Action given with `--action`, performed by every confirm key
+    pub action: Option<Action>,
+    /// Output format of `dump`, given with `--format`
+    pub format: DumpFormat,
+    /// Click action of `click`, given with `--button`
+    pub button: Option<Action>,
+    /// Whether `--help` was given
+    pub help: bool,
+}
+
+impl Cli {
+    /// Parse the arguments that follow the program name
+    ///
+    /// Options taking a value accept it as the next argument or after `=`
+    /// (`--config path` or `--config=path`). At most one command may be given,
+    /// before or after the options.
+    ///
+    /// # Returns
+    /// - `Ok(Cli)` with the parsed options
+    /// - `Err` for unknown options or commands, and missing or invalid values
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| anyhow!("Missing value for {}", name))
+            };
+
+            match name.as_str() {
+                "-c" | "--config" => cli.config = Some(PathBuf::from(value()?)),
+                "--rows" => cli.rows = Some(parse_count(&name, &value()?)?),
+                "--cols" => cli.cols = Some(parse_count(&name, &value()?)?),
+                "-m" | "--monitor" => cli.monitor = Some(value()?),
+                "-a" | "--action" => {
+                    let action = value()?;
+                    cli.action = Some(Action::from_name(&action).ok_or_else(|| {
+                        anyhow!(
+                            "Invalid value for --action: '{}'. Must be one of: {}",
+                            action,
+                            Action::names()
+                        )
+                    })?);
+                }
+                "--format" => {
+                    let format = value()?;
+                    cli.format = DumpFormat::from_name(&format).ok_or_else(|| {
+                        anyhow!(
+                            "Invalid value for --format: '{}'. Must be one of: {}",
+                            format,
+                            DumpFormat::names()
+                        )
+                    })?;
+                }
+                "--button" => {
+                    let button = value()?;
+                    cli.button = Some(match button.to_ascii_lowercase().as_str() {
+                        "left" => Action::LeftClick,
+                        "right" => Action::RightClick,
+                        "middle" => Action::MiddleClick,
+                        _ => {
+                            return Err(anyhow!(
+                                "Invalid value for --button: '{}'. Must be one of: left, right, middle",
+                                button
+                            ))
+                        }
+                    });
+                }
+                "-h" | "--help" => cli.help = true,
+                _ if !arg.starts_with('-') => match &mut cli.command {
+                    Command::Overlay => {
+                        cli.command = match arg.as_str() {
+                            "check-config" => Command::CheckConfig,
+                            "dump" => Command::Dump,
+                            "click" => Command::Click(String::new()),
+                            "move" => Command::Move(String::new()),
+                            _ => return Err(anyhow!("Unknown command: '{}'", arg)),
+                        };
+                    }
+                    Command::Click(label) | Command::Move(label) if label.is_empty() => {
+                        *label = arg.to_lowercase();
+                    }
+                    _ => return Err(anyhow!("Unexpected argument: '{}'", arg)),
+                },
+                _ => return Err(anyhow!("Unknown argument: '{}'", arg)),
+            }
+        }
+
+        match &cli.command {
+            Command::Click(label) if label.is_empty() => Err(anyhow!("Missing label for click")),
+            Command::Move(label) if label.is_empty() => Err(anyhow!("Missing label for move")),
+            _ => Ok(cli),
+        }
+    }
+}
+
+/// Parse the value of a grid size option
+fn parse_count(name: &str, value: &str) -> Result<u32> {
+    value.parse().map_err(|_| {
+        anyhow!(
+            "Invalid value for {}: '{}'. Expected a whole number",
+            name,
+            value
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli> {
+        Cli::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_no_arguments() {
+        assert_eq!(parse(&[]).unwrap(), Cli::default());
+    }
+
+    #[test]
+    fn test_config_flag() {
+        let expected = Some(PathBuf::from("/tmp/grid.toml"));
+        assert_eq!(parse(&["--config", "/tmp/grid.toml"]).unwrap().config, expected);
+        assert_eq!(parse(&["--config=/tmp/grid.toml"]).unwrap().config, expected);
+        assert_eq!(parse(&["-c", "/tmp/grid.toml"]).unwrap().config, expected);
+        assert!(parse(&["-h"]).unwrap().help);
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(parse(&[]).unwrap().command, Command::Overlay);
+        assert_eq!(parse(&["check-config"]).unwrap().command, Command::CheckConfig);
+
+        let cli = parse(&["--rows", "6", "dump", "--cols=12"]).unwrap();
+        assert_eq!(cli.command, Command::Dump);
+        assert_eq!((cli.rows, cli.cols), (Some(6), Some(12)));
+        assert_eq!(cli.format, DumpFormat::Json);
+        assert_eq!(parse(&["dump", "--format", "csv"]).unwrap().format, DumpFormat::Csv);
+    }
+
+    #[test]
+    fn test_label_commands() {
+        assert_eq!(parse(&["click", "AJ"]).unwrap().command, Command::Click("aj".to_string()));
+        assert_eq!(parse(&["move", "sd"]).unwrap().command, Command::Move("sd".to_string()));
+
+        let cli = parse(&["click", "--button", "right", "aj", "--monitor=DP-2"]).unwrap();
+        assert_eq!(cli.command, Command::Click("aj".to_string()));
+        assert_eq!(cli.button, Some(Action::RightClick));
+        assert_eq!(cli.monitor.as_deref(), Some("DP-2"));
+    }
+
+    #[test]
+    fn test_overlay_options() {
+        let cli = parse(&["--monitor", "DP-2", "--action", "right-click"]).unwrap();
+        assert_eq!(
+            cli,
+            Cli {
+                monitor: Some("DP-2".to_string()),
+                action: Some(Action::RightClick),
+                ..Cli::default()
+            }
+        );
+        assert_eq!(parse(&["-a", "left-click"]).unwrap().action, Some(Action::LeftClick));
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let error = |args: &[&str]| parse(args).unwrap_err().to_string();
+
+        assert_eq!(error(&["--config"]), "Missing value for --config");
+        assert_eq!(error(&["--grid"]), "Unknown argument: '--grid'");
+        assert_eq!(error(&["--rows", "six"]), "Invalid value for --rows: 'six'. Expected a whole number");
+        assert!(error(&["--action", "click"]).starts_with("Invalid value for --action: 'click'"));
+        assert!(error(&["--format", "xml"]).starts_with("Invalid value for --format: 'xml'"));
+        assert_eq!(error(&["launch"]), "Unknown command: 'launch'");
+        assert_eq!(error(&["dump", "check-config"]), "Unexpected argument: 'check-config'");
+        assert_eq!(error(&["click"]), "Missing label for click");
+        assert_eq!(error(&["move", "aj", "sd"]), "Unexpected argument: 'sd'");
+        assert!(error(&["click", "aj", "--button", "up"]).starts_with("Invalid value for --button: 'up'"));
+    }
+}
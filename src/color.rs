@@ -0,0 +1,297 @@
+// Color module for HyprGrid
+//
+// This module parses the color values of hg_config.conf. Besides CSS-style hex
+// colors it accepts the syntax of hyprland.conf, so colors can be copied over
+// from the compositor's theme as-is.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An sRGB color with straight (non-premultiplied) alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Create an opaque color
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Create a color with an alpha channel (0 = transparent, 255 = opaque)
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Parse a color in any supported syntax
+    ///
+    /// # Supported Formats
+    /// - `#RGB`, `#RGBA`, `#RRGGBB`, `#RRGGBBAA` - CSS hex colors
+    /// - `rgb(r, g, b)`, `rgba(r, g, b, a)` - decimal channels (0-255) and an
+    ///   alpha between 0.0 and 1.0, as in CSS
+    /// - `0xAARRGGBB` - Hyprland's legacy hex syntax, alpha first
+    /// - `rgb(RRGGBB)`, `rgba(RRGGBBAA)` - Hyprland's hex syntax
+    ///
+    /// # Returns
+    /// - `Ok(Color)` if the value is a valid color
+    /// - `Err` naming the format that was recognized and what is wrong with it
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hyprgrid::color::Color;
+    ///
+    /// assert_eq!(Color::parse("rgba(33ccffee)")?, Color::parse("0xee33ccff")?);
+    /// ```
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            return parse_css_hex(hex);
+        }
+        if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            let bytes = parse_hex_digits(hex, 8, value).context("Expected 0xAARRGGBB")?;
+            return Ok(Color::rgba(bytes[1], bytes[2], bytes[3], bytes[0]));
+        }
+        if let Some(args) = function_args(value, "rgba") {
+            return parse_function(args, true);
+        }
+        if let Some(args) = function_args(value, "rgb") {
+            return parse_function(args, false);
+        }
+
+        Err(anyhow!(
+            "'{}' is not a color. Use #RRGGBB, #RRGGBBAA, rgba(r, g, b, a), \
+             0xAARRGGBB or rgba(RRGGBBAA)",
+            value
+        ))
+    }
+}
+
+impl FromStr for Color {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Color::parse(value)
+    }
+}
+
+impl fmt::Display for Color {
+    /// Format as `#RRGGBB`, or `#RRGGBBAA` when not fully opaque
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02X}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Color::parse(&value).map_err(|e| serde::de::Error::custom(format!("{:#}", e)))
+    }
+}
+
+impl From<Color> for iced::Color {
+    fn from(color: Color) -> Self {
+        iced::Color::from_rgba8(color.r, color.g, color.b, color.a as f32 / 255.0)
+    }
+}
+
+/// Get the arguments of `name(...)`, if `value` has that form
+fn function_args<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parse the digits of a `#` color (3, 4, 6 or 8 of them)
+fn parse_css_hex(hex: &str) -> Result<Color> {
+    let value = format!("#{}", hex);
+    check_hex_digits(hex, &value)?;
+
+    let digits: String = match hex.len() {
+        // Each digit is doubled: #F80 is #FF8800
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        n => {
+            return Err(anyhow!(
+                "'{}' has {} hex digits, expected 3, 4, 6 or 8 (#RGB, #RGBA, #RRGGBB or #RRGGBBAA)",
+                value,
+                n
+            ))
+        }
+    };
+
+    let bytes = parse_hex_digits(&digits, digits.len(), &value)?;
+    Ok(Color::rgba(bytes[0], bytes[1], bytes[2], bytes.get(3).copied().unwrap_or(255)))
+}
+
+/// Check that every character of `hex` is a hex digit
+///
+/// `value` is the whole color as written, for the error message.
+fn check_hex_digits(hex: &str, value: &str) -> Result<()> {
+    match hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        Some(c) => Err(anyhow!("'{}' in color '{}' is not a hex digit", c, value)),
+        None => Ok(()),
+    }
+}
+
+/// Parse exactly `count` hex digits into bytes
+///
+/// `value` is the whole color as written, for error messages.
+fn parse_hex_digits(hex: &str, count: usize, value: &str) -> Result<Vec<u8>> {
+    check_hex_digits(hex, value)?;
+    if hex.len() != count {
+        return Err(anyhow!(
+            "'{}' has {} hex digits, expected {}",
+            value,
+            hex.len(),
+            count
+        ));
+    }
+
+    Ok((0..count)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Parse the arguments of `rgb(...)` or `rgba(...)`
+///
+/// Comma-separated arguments are decimal channels; a single argument is
+/// Hyprland's hex form.
+fn parse_function(args: &str, with_alpha: bool) -> Result<Color> {
+    let name = if with_alpha { "rgba" } else { "rgb" };
+
+    if !args.contains(',') {
+        let (digits, format) = if with_alpha {
+            (8, "rgba(RRGGBBAA) or rgba(r, g, b, a)")
+        } else {
+            (6, "rgb(RRGGBB) or rgb(r, g, b)")
+        };
+        let value = format!("{}({})", name, args);
+        let bytes = parse_hex_digits(args.trim(), digits, &value)
+            .with_context(|| format!("Expected {}", format))?;
+        let alpha = bytes.get(3).copied().unwrap_or(255);
+        return Ok(Color::rgba(bytes[0], bytes[1], bytes[2], alpha));
+    }
+
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    let expected = if with_alpha { 4 } else { 3 };
+    if parts.len() != expected {
+        return Err(anyhow!(
+            "{}({}) has {} arguments, expected {}",
+            name,
+            args,
+            parts.len(),
+            expected
+        ));
+    }
+
+    let channel = |index: usize, label: &str| -> Result<u8> {
+        parts[index].parse::<u8>().with_context(|| {
+            format!(
+                "{} channel '{}' in {}({}) must be a whole number between 0 and 255",
+                label, parts[index], name, args
+            )
+        })
+    };
+    let (r, g, b) = (channel(0, "Red")?, channel(1, "Green")?, channel(2, "Blue")?);
+
+    let a = if with_alpha {
+        let alpha = parts[3]
+            .parse::<f32>()
+            .ok()
+            .filter(|a| (0.0..=1.0).contains(a))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Alpha '{}' in rgba({}) must be a number between 0.0 and 1.0",
+                    parts[3],
+                    args
+                )
+            })?;
+        (alpha * 255.0).round() as u8
+    } else {
+        255
+    };
+
+    Ok(Color::rgba(r, g, b, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_hex_colors() {
+        assert_eq!(Color::parse("#FFF").unwrap(), Color::rgb(255, 255, 255));
+        assert_eq!(Color::parse("#f80").unwrap(), Color::rgb(0xFF, 0x88, 0x00));
+        assert_eq!(Color::parse("#0008").unwrap(), Color::rgba(0, 0, 0, 0x88));
+        assert_eq!(Color::parse("#33CCFF").unwrap(), Color::rgb(0x33, 0xCC, 0xFF));
+        assert_eq!(Color::parse("#FFFFFF80").unwrap(), Color::rgba(255, 255, 255, 0x80));
+        assert_eq!(Color::parse("  #000000 ").unwrap(), Color::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_css_rgba_functions() {
+        assert_eq!(Color::parse("rgb(255, 128, 0)").unwrap(), Color::rgb(255, 128, 0));
+        assert_eq!(Color::parse("rgba(0,0,0,0.5)").unwrap(), Color::rgba(0, 0, 0, 128));
+        assert_eq!(Color::parse("rgba(10, 20, 30, 1)").unwrap(), Color::rgb(10, 20, 30));
+    }
+
+    #[test]
+    fn test_hyprland_colors() {
+        assert_eq!(Color::parse("0xee33ccff").unwrap(), Color::rgba(0x33, 0xCC, 0xFF, 0xEE));
+        assert_eq!(Color::parse("rgba(33ccffee)").unwrap(), Color::rgba(0x33, 0xCC, 0xFF, 0xEE));
+        assert_eq!(Color::parse("rgb(595959)").unwrap(), Color::rgb(0x59, 0x59, 0x59));
+    }
+
+    #[test]
+    fn test_precise_errors() {
+        let error = |value: &str| format!("{:#}", Color::parse(value).unwrap_err());
+
+        assert!(error("#GGGGGG").contains("'G' in color '#GGGGGG' is not a hex digit"));
+        assert!(error("#FFFFF").contains("'#FFFFF' has 5 hex digits, expected 3, 4, 6 or 8"));
+        assert!(error("0xFFFFFF").contains("Expected 0xAARRGGBB: '0xFFFFFF' has 6 hex digits, expected 8"));
+        assert!(error("rgba(33ccff)").contains("'rgba(33ccff)' has 6 hex digits, expected 8"));
+        assert!(error("rgba(0, 0, 0)").contains("has 3 arguments, expected 4"));
+        assert!(error("rgb(300, 0, 0)").contains("Red channel '300'"));
+        assert!(error("rgba(0, 0, 0, 2)").contains("Alpha '2'"));
+        assert!(error("white").contains("'white' is not a color"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for value in ["#33CCFF", "#FFFFFF80"] {
+            let color = Color::parse(value).unwrap();
+            assert_eq!(color.to_string(), value);
+        }
+        assert_eq!(Color::parse("rgba(0,0,0,0.5)").unwrap().to_string(), "#00000080");
+    }
+
+    #[test]
+    fn test_deserialize_and_convert() {
+        #[derive(Debug, Deserialize)]
+        struct Test {
+            color: Color,
+        }
+
+        let test: Test = toml::from_str("color = \"0x80FF0000\"").unwrap();
+        let color = iced::Color::from(test.color);
+        assert_eq!((color.r, color.g, color.b), (1.0, 0.0, 0.0));
+        assert!((color.a - 128.0 / 255.0).abs() < 1e-6);
+
+        let error = toml::from_str::<Test>("color = \"#12345\"").unwrap_err();
+        assert!(error.to_string().contains("'#12345' has 5 hex digits"));
+    }
+}
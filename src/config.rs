@@ -3,23 +3,82 @@
 
 use anyhow::{anyhow, Context, Result};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+
+use crate::color::Color;
+use crate::grid::{Alphabet, CellCoordinates, LabelMode, LabelOptions};
+use crate::hyprland;
+use crate::input::{Action, Key, KeyBindings};
+use crate::layout::KeyboardLayout;
 
 /// Represents monitor information detected at runtime from Hyprland
 #[derive(Debug, Clone, Deserialize)]
 pub struct MonitorInfo {
     /// Monitor name as reported by Hyprland (e.g., "DP-1", "HDMI-A-1")
     pub name: String,
-    /// Horizontal resolution in pixels
+    /// Make, model and serial as reported by Hyprland (e.g., "AOC 27B30H 1AQO9XA001727")
+    #[serde(default)]
+    pub description: String,
+    /// Horizontal resolution in physical pixels (of the unrotated mode)
     pub width: u32,
-    /// Vertical resolution in pixels
+    /// Vertical resolution in physical pixels (of the unrotated mode)
     pub height: u32,
+    /// Horizontal position of the monitor in the global layout (may be negative)
+    #[serde(default)]
+    pub x: i32,
+    /// Vertical position of the monitor in the global layout (may be negative)
+    #[serde(default)]
+    pub y: i32,
+    /// Fractional scaling factor (e.g., 1.5)
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Output transform (0-7, odd values are rotated by 90 or 270 degrees)
+    #[serde(default)]
+    pub transform: u32,
+    /// Space reserved by layer surfaces such as bars: `[left, top, right, bottom]`
+    #[serde(default)]
+    pub reserved: [u32; 4],
     /// Whether this monitor is currently focused
     pub focused: bool,
 }
 
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl MonitorInfo {
+    /// Get the monitor size in logical pixels
+    ///
+    /// This is the size of the monitor in the global layout, which is the
+    /// coordinate space used by Hyprland and pointer-injection tools. The
+    /// physical mode is divided by the scale factor, and width and height are
+    /// swapped for transforms that rotate the output by 90 or 270 degrees.
+    pub fn logical_size(&self) -> (u32, u32) {
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+        let width = (self.width as f64 / scale).round() as u32;
+        let height = (self.height as f64 / scale).round() as u32;
+
+        if self.transform % 2 == 1 {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Get the monitor's area in the global layout, in logical pixels
+    pub fn logical_area(&self) -> CellCoordinates {
+        let (width, height) = self.logical_size();
+        CellCoordinates {
+            x: self.x,
+            y: self.y,
+            width,
+            height,
+        }
+    }
+}
+
 /// Monitor orientation detected from dimensions
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Orientation {
@@ -69,6 +128,9 @@ impl GridDimensions {
     /// - `config_cols`: Configured columns from config file (for landscape)
     /// - `monitor`: The monitor to calculate dimensions for
     ///
+    /// Orientation is decided from the monitor's logical size, so rotated
+    /// outputs (`transform`) are treated according to how they are displayed.
+    ///
     /// # Logic
     /// - **Landscape** (width > height): Use configured rows and cols as-is
     /// - **Portrait** (width < height): Swap rows and cols
@@ -76,7 +138,8 @@ impl GridDimensions {
     ///
     /// This ensures grid cells remain roughly square regardless of orientation.
     pub fn calculate(config_rows: u32, config_cols: u32, monitor: &MonitorInfo) -> Self {
-        let orientation = Orientation::from_dimensions(monitor.width, monitor.height);
+        let (width, height) = monitor.logical_size();
+        let orientation = Orientation::from_dimensions(width, height);
 
         let (rows, cols) = match orientation {
             Orientation::Landscape | Orientation::Square => (config_rows, config_cols),
@@ -96,63 +159,364 @@ impl GridDimensions {
     }
 }
 
+/// How the grid treats monitor areas reserved by bars and docks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReservedArea {
+    /// Cover the whole monitor, ignoring reserved areas
+    #[default]
+    Ignore,
+    /// Cover only the usable area, leaving reserved strips uncovered
+    Exclude,
+    /// Give each reserved strip its own dedicated row or column of cells
+    ///
+    /// The grid can then have up to two more rows and columns than
+    /// `grid_rows` × `grid_cols`, which the label capacity must allow for.
+    Dedicated,
+}
+
+/// Where labels are drawn inside their cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelPosition {
+    /// In the middle of the cell
+    #[default]
+    Center,
+    /// In the top-left corner, leaving the cell center visible
+    Corner,
+}
+
+/// Appearance of the overlay (`[theme]` table)
+///
+/// The defaults put light text on a dark backdrop behind every label, so
+/// labels stay readable on light and dark wallpapers.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Label font: "monospace", "sans-serif", "serif" or a font family name
+    pub font_family: String,
+    /// Label font size in pixels (fitted to the cell size when unset)
+    pub font_size: Option<u32>,
+    /// Label text color
+    pub foreground: Color,
+    /// Color of the backdrop drawn behind each label
+    pub background: Color,
+    /// Color of the typed part of matching labels and of the final candidate
+    pub highlight: Color,
+    /// Color laid over the whole screen behind the grid
+    pub overlay_background: Color,
+    /// Where labels are drawn inside their cells
+    pub label_position: LabelPosition,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            font_family: "monospace".to_string(),
+            font_size: None,
+            foreground: Color::rgb(0xFF, 0xFF, 0xFF),
+            background: Color::rgba(0x00, 0x00, 0x00, 0x99),
+            highlight: Color::rgb(0xFF, 0xCC, 0x00),
+            overlay_background: Color::rgba(0x00, 0x00, 0x00, 0x4D),
+            label_position: LabelPosition::Center,
+        }
+    }
+}
+
+impl Theme {
+    /// Check the theme settings
+    ///
+    /// Ensures that the font family is not empty and the font size is
+    /// reasonable (6-96 pixels). Colors are checked while parsing.
+    fn validate(&self) -> Result<()> {
+        if self.font_family.trim().is_empty() {
+            return Err(anyhow!(
+                "Invalid theme.font_family: must not be empty.\n\
+                 Use \"monospace\", \"sans-serif\", \"serif\" or a font name."
+            ));
+        }
+
+        if let Some(size) = self.font_size {
+            if !(6..=96).contains(&size) {
+                return Err(anyhow!(
+                    "Invalid theme.font_size: {}. Must be between 6 and 96 pixels.\n\
+                     Leave it unset to fit labels to the cell size.",
+                    size
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Main configuration structure for HyprGrid
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct HyprGridConfig {
     /// Number of rows in the grid overlay (for landscape orientation)
+    #[serde(default = "default_grid_rows")]
     pub grid_rows: u32,
     /// Number of columns in the grid overlay (for landscape orientation)
+    #[serde(default = "default_grid_cols")]
     pub grid_cols: u32,
     /// Whether to draw borders between grid cells
     #[serde(default = "default_border_enabled")]
     pub border_enabled: bool,
-    /// Color of the grid cell borders (hex format)
+    /// Color of the grid cell borders
     #[serde(default = "default_border_color")]
-    pub border_color: String,
+    pub border_color: Color,
     /// Width of the grid cell borders in pixels
     #[serde(default = "default_border_width")]
     pub border_width: u32,
+    /// How to handle areas reserved by bars and docks
+    #[serde(default)]
+    pub reserved_area: ReservedArea,
+    /// Number of rows when refining a cell into a sub-grid
+    #[serde(default = "default_subgrid_size")]
+    pub subgrid_rows: u32,
+    /// Number of columns when refining a cell into a sub-grid
+    #[serde(default = "default_subgrid_size")]
+    pub subgrid_cols: u32,
+    /// Keyboard layout preset used to derive the label alphabet (e.g., "colemak")
+    #[serde(default)]
+    pub keyboard_layout: Option<String>,
+    /// Custom label alphabet in priority order (overrides the layout preset)
+    #[serde(default)]
+    pub label_alphabet: Option<String>,
+    /// How labels are assigned to cells (e.g., "sequential", "coordinates")
+    #[serde(default)]
+    pub label_mode: LabelMode,
+    /// Show the grid on a layer-shell surface instead of a regular window
+    #[serde(default = "default_layer_shell")]
+    pub layer_shell: bool,
+    /// Label font, colors and placement
+    #[serde(default)]
+    pub theme: Theme,
+    /// Confirm keys and their actions (`[keys]` table), added to the defaults
+    #[serde(default)]
+    pub keys: BTreeMap<String, Action>,
+    /// Per-monitor overrides (`[monitor."DP-1"]` tables), keyed by monitor
+    /// name or description
+    #[serde(default)]
+    pub monitor: BTreeMap<String, MonitorOverride>,
+}
+
+/// Settings overridden for one monitor (`[monitor."<name>"]` table)
+///
+/// Every setting is optional and falls back to the global value. Theme
+/// settings are overridden one by one, so `[monitor."DP-1".theme]` only needs
+/// the values that differ.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonitorOverride {
+    pub grid_rows: Option<u32>,
+    pub grid_cols: Option<u32>,
+    pub keyboard_layout: Option<String>,
+    pub label_alphabet: Option<String>,
+    pub label_mode: Option<LabelMode>,
+    #[serde(default)]
+    pub theme: ThemeOverride,
+}
+
+/// Theme settings overridden for one monitor
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeOverride {
+    pub font_family: Option<String>,
+    pub font_size: Option<u32>,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub highlight: Option<Color>,
+    pub overlay_background: Option<Color>,
+    pub label_position: Option<LabelPosition>,
+}
+
+impl Theme {
+    /// Apply a monitor's theme overrides
+    fn merged(&self, overrides: &ThemeOverride) -> Theme {
+        Theme {
+            font_family: overrides
+                .font_family
+                .clone()
+                .unwrap_or_else(|| self.font_family.clone()),
+            font_size: overrides.font_size.or(self.font_size),
+            foreground: overrides.foreground.unwrap_or(self.foreground),
+            background: overrides.background.unwrap_or(self.background),
+            highlight: overrides.highlight.unwrap_or(self.highlight),
+            overlay_background: overrides.overlay_background.unwrap_or(self.overlay_background),
+            label_position: overrides.label_position.unwrap_or(self.label_position),
+        }
+    }
+}
+
+fn default_grid_rows() -> u32 {
+    10
+}
+
+fn default_grid_cols() -> u32 {
+    20
 }
 
 fn default_border_enabled() -> bool {
     true
 }
 
-fn default_border_color() -> String {
-    "#FFFFFF".to_string()
+fn default_border_color() -> Color {
+    Color::rgb(0xFF, 0xFF, 0xFF)
 }
 
 fn default_border_width() -> u32 {
     1
 }
 
+fn default_subgrid_size() -> u32 {
+    5
+}
+
+fn default_layer_shell() -> bool {
+    true
+}
+
+impl Default for HyprGridConfig {
+    /// The built-in configuration, used when no configuration file exists
+    fn default() -> Self {
+        HyprGridConfig {
+            grid_rows: default_grid_rows(),
+            grid_cols: default_grid_cols(),
+            border_enabled: default_border_enabled(),
+            border_color: default_border_color(),
+            border_width: default_border_width(),
+            reserved_area: ReservedArea::default(),
+            subgrid_rows: default_subgrid_size(),
+            subgrid_cols: default_subgrid_size(),
+            keyboard_layout: None,
+            label_alphabet: None,
+            label_mode: LabelMode::default(),
+            layer_shell: default_layer_shell(),
+            theme: Theme::default(),
+            keys: BTreeMap::new(),
+            monitor: BTreeMap::new(),
+        }
+    }
+}
+
+/// Environment variable naming the configuration file to use
+pub const CONFIG_ENV: &str = "HYPRGRID_CONFIG";
+
+/// Find the configuration file to load
+///
+/// # Search Order
+/// 1. `explicit` (the `--config` flag)
+/// 2. `$HYPRGRID_CONFIG`
+/// 3. `$XDG_CONFIG_HOME/hypr/hg_config.conf`
+/// 4. `$XDG_CONFIG_HOME/hyprgrid/config.toml`
+/// 5. `~/.config/hypr/hg_config.conf`
+/// 6. `~/.config/hyprgrid/config.toml`
+///
+/// A file named by the flag or the environment variable must exist; the
+/// other locations are skipped when missing. A relative `$XDG_CONFIG_HOME`
+/// is ignored, as the XDG specification requires.
+///
+/// # Arguments
+/// * `explicit` - Path given on the command line, if any
+/// * `env` - Looks up an environment variable (`std::env::var` in practice)
+/// * `exists` - Checks whether a file exists
+///
+/// # Returns
+/// - `Ok(Some(path))` for the first configuration file found
+/// - `Ok(None)` if there is none, so the built-in defaults apply
+/// - `Err` if an explicitly named file does not exist
+pub fn find_config_path(
+    explicit: Option<&Path>,
+    env: impl Fn(&str) -> Option<String>,
+    exists: impl Fn(&Path) -> bool,
+) -> Result<Option<PathBuf>> {
+    let named = explicit
+        .map(|path| (path.to_path_buf(), "--config"))
+        .or_else(|| {
+            env(CONFIG_ENV)
+                .filter(|value| !value.is_empty())
+                .map(|value| (PathBuf::from(value), CONFIG_ENV))
+        });
+    if let Some((path, source)) = named {
+        if !exists(&path) {
+            return Err(anyhow!(
+                "Configuration file not found: {} (from {})",
+                path.display(),
+                source
+            ));
+        }
+        return Ok(Some(path));
+    }
+
+    let xdg = env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute());
+    let home = env("HOME")
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".config"));
+
+    let candidates = xdg.into_iter().chain(home).flat_map(|dir| {
+        [
+            dir.join("hypr").join("hg_config.conf"),
+            dir.join("hyprgrid").join("config.toml"),
+        ]
+    });
+    for path in candidates {
+        if exists(&path) {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
+
 impl HyprGridConfig {
-    /// Load and parse the HyprGrid configuration file
+    /// Find and load the HyprGrid configuration
     ///
-    /// # Configuration File Location
-    /// The configuration file should be at: `~/.config/hypr/hg_config.conf`
+    /// The file is looked up as described in [`find_config_path`]. Without
+    /// any configuration file the built-in defaults are used.
+    ///
+    /// # Arguments
+    /// * `explicit` - Path given with `--config`, if any
     ///
     /// # Returns
-    /// - `Ok(HyprGridConfig)` if the file is successfully loaded and validated
+    /// - `Ok((config, Some(path)))` with the loaded file
+    /// - `Ok((config, None))` with the defaults if no file exists
     /// - `Err` with detailed error message if loading or validation fails
     ///
     /// # Example
     /// ```no_run
     /// use hyprgrid::config::HyprGridConfig;
     ///
-    /// let config = HyprGridConfig::load()?;
+    /// let (config, path) = HyprGridConfig::load(None)?;
     /// println!("Grid: {}x{}", config.grid_rows, config.grid_cols);
     /// ```
-    pub fn load() -> Result<Self> {
-        let config_path = Self::get_config_path()?;
+    pub fn load(explicit: Option<&Path>) -> Result<(Self, Option<PathBuf>)> {
+        let path = find_config_path(
+            explicit,
+            |name| std::env::var(name).ok(),
+            |path| path.is_file(),
+        )?;
+
+        match path {
+            Some(path) => Ok((Self::load_from(&path)?, Some(path))),
+            None => Ok((Self::default(), None)),
+        }
+    }
 
+    /// Load and parse a HyprGrid configuration file
+    ///
+    /// # Returns
+    /// - `Ok(HyprGridConfig)` if the file is successfully loaded and validated
+    /// - `Err` with detailed error message if loading or validation fails
+    pub fn load_from(config_path: &Path) -> Result<Self> {
         // Read the configuration file
-        let config_content = fs::read_to_string(&config_path)
+        let config_content = fs::read_to_string(config_path)
             .with_context(|| {
                 format!(
                     "Failed to read configuration file at: {}\n\
-                     Please ensure the file exists and is readable.\n\
-                     Expected location: ~/.config/hypr/hg_config.conf",
+                     Please ensure the file is readable.",
                     config_path.display()
                 )
             })?;
@@ -179,17 +543,142 @@ impl HyprGridConfig {
         Ok(config)
     }
 
-    /// Get the full path to the configuration file
-    fn get_config_path() -> Result<PathBuf> {
-        let home_dir = std::env::var("HOME")
-            .context("HOME environment variable is not set")?;
+    /// Find the `[monitor."..."]` table that applies to a monitor
+    ///
+    /// A table whose key is the monitor's name wins. Otherwise the key is
+    /// matched against the monitor's description, either whole or as a part
+    /// of it (e.g., the model "24BL550J"); the first such key in alphabetical
+    /// order is used.
+    ///
+    /// # Returns
+    /// The matching key and its overrides, or `None` if no table applies
+    pub fn monitor_override(&self, monitor: &MonitorInfo) -> Option<(&str, &MonitorOverride)> {
+        self.monitor
+            .get_key_value(&monitor.name)
+            .or_else(|| {
+                self.monitor.iter().find(|(key, _)| {
+                    !key.is_empty()
+                        && !monitor.description.is_empty()
+                        && monitor.description.contains(key.as_str())
+                })
+            })
+            .map(|(key, overrides)| (key.as_str(), overrides))
+    }
+
+    /// Get the configuration that applies to a monitor
+    ///
+    /// Settings from the monitor's `[monitor."..."]` table (see
+    /// [`monitor_override`](Self::monitor_override)) replace the global ones;
+    /// without a matching table this is a copy of the global configuration.
+    /// Overrides are validated by [`load`](Self::load), so the result is valid
+    /// too.
+    pub fn for_monitor(&self, monitor: &MonitorInfo) -> HyprGridConfig {
+        match self.monitor_override(monitor) {
+            Some((_, overrides)) => self.with_override(overrides),
+            None => self.clone(),
+        }
+    }
 
-        let config_path = PathBuf::from(home_dir)
-            .join(".config")
-            .join("hypr")
-            .join("hg_config.conf");
+    /// Apply a set of overrides to a copy of this configuration
+    ///
+    /// The copy has no `[monitor]` tables of its own, as they are resolved.
+    fn with_override(&self, overrides: &MonitorOverride) -> HyprGridConfig {
+        let mut config = self.clone();
+        config.monitor.clear();
 
-        Ok(config_path)
+        if let Some(rows) = overrides.grid_rows {
+            config.grid_rows = rows;
+        }
+        if let Some(cols) = overrides.grid_cols {
+            config.grid_cols = cols;
+        }
+        if let Some(layout) = &overrides.keyboard_layout {
+            config.keyboard_layout = Some(layout.clone());
+        }
+        if let Some(letters) = &overrides.label_alphabet {
+            config.label_alphabet = Some(letters.clone());
+        }
+        if let Some(mode) = overrides.label_mode {
+            config.label_mode = mode;
+        }
+        config.theme = self.theme.merged(&overrides.theme);
+
+        config
+    }
+
+    /// Replace the grid size with values given on the command line
+    ///
+    /// Like the configuration file, the values describe a landscape monitor
+    /// and are swapped for portrait ones. They take precedence over
+    /// `[monitor."..."]` tables, so apply them after
+    /// [`for_monitor`](Self::for_monitor).
+    ///
+    /// # Returns
+    /// - `Ok(HyprGridConfig)` with the new grid size
+    /// - `Err` if the resulting configuration is invalid (e.g., too many
+    ///   cells for the label alphabet)
+    pub fn with_grid_size(&self, rows: Option<u32>, cols: Option<u32>) -> Result<HyprGridConfig> {
+        let mut config = self.clone();
+        config.grid_rows = rows.unwrap_or(self.grid_rows);
+        config.grid_cols = cols.unwrap_or(self.grid_cols);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Build the label options described by this configuration
+    ///
+    /// The alphabet is taken from `label_alphabet` if set, otherwise from the
+    /// home row of the `keyboard_layout` preset, otherwise QWERTY. The layout
+    /// also drives the typing-effort model of the ergonomic `label_mode`.
+    pub fn label_options(&self) -> Result<LabelOptions> {
+        let layout = match &self.keyboard_layout {
+            Some(name) => KeyboardLayout::from_name(name).ok_or_else(|| {
+                anyhow!(
+                    "Invalid keyboard_layout: '{}'. Must be one of: {}.",
+                    name,
+                    KeyboardLayout::preset_names()
+                )
+            })?,
+            None => &crate::layout::QWERTY,
+        };
+
+        let alphabet = match &self.label_alphabet {
+            Some(letters) => Alphabet::new(letters).context("Invalid label_alphabet")?,
+            None => Alphabet::new(&layout.home_row_alphabet())?,
+        };
+
+        Ok(LabelOptions {
+            alphabet,
+            mode: self.label_mode,
+            layout,
+        })
+    }
+
+    /// Build the key bindings described by the `[keys]` table
+    ///
+    /// Entries are added to the default bindings (SPACE for left click, ENTER
+    /// for right click, and so on), replacing the default of the same key.
+    /// Letters cannot be bound, as they type labels.
+    pub fn key_bindings(&self) -> Result<KeyBindings> {
+        let mut bindings = KeyBindings::default();
+
+        for (name, action) in &self.keys {
+            let key = Key::from_name(name).ok_or_else(|| {
+                anyhow!(
+                    "Invalid key in [keys]: '{}'. Use \"space\", \"enter\" or a single character.",
+                    name
+                )
+            })?;
+            if matches!(key, Key::Char(c) if c.is_alphabetic()) {
+                return Err(anyhow!(
+                    "Invalid key in [keys]: '{}'. Letters are used to type labels.",
+                    name
+                ));
+            }
+            bindings.bind(key, *action);
+        }
+
+        Ok(bindings)
     }
 
     /// Validate the configuration values
@@ -197,7 +686,14 @@ impl HyprGridConfig {
     /// Ensures that:
     /// - Grid dimensions are within reasonable bounds (2-50)
     /// - Border width is reasonable (0-10 pixels)
-    /// - Border color is valid hex format
+    /// - Sub-grid dimensions are within reasonable bounds (2-26)
+    /// - The theme has a font, a reasonable font size and valid colors
+    /// - The keyboard layout and label alphabet are valid
+    /// - The `[keys]` table only binds keys that can confirm a label
+    /// - The alphabet can label every cell of the grid and the sub-grid
+    /// - Every `[monitor."..."]` table gives a valid configuration as well
+    ///
+    /// Colors are already checked while parsing (see [`Color::parse`]).
     fn validate(&self) -> Result<()> {
         // Validate grid dimensions
         if self.grid_rows < 2 || self.grid_rows > 50 {
@@ -216,6 +712,38 @@ impl HyprGridConfig {
             ));
         }
 
+        // Validate sub-grid dimensions
+        if !(2..=26).contains(&self.subgrid_rows) || !(2..=26).contains(&self.subgrid_cols) {
+            return Err(anyhow!(
+                "Invalid sub-grid size: {}x{} (rows x cols). Each must be between 2 and 26.\n\
+                 A reasonable value would be 3-6.",
+                self.subgrid_rows,
+                self.subgrid_cols
+            ));
+        }
+
+        // Validate label alphabet and its capacity. Dedicated reserved strips
+        // can add a row or column on every edge.
+        let options = self.label_options()?;
+        let (grid_name, strips) = match self.reserved_area {
+            ReservedArea::Dedicated => ("Grid with dedicated reserved strips", 2),
+            ReservedArea::Ignore | ReservedArea::Exclude => ("Grid", 0),
+        };
+        for (name, rows, cols) in [
+            (grid_name, self.grid_rows + strips, self.grid_cols + strips),
+            ("Sub-grid", self.subgrid_rows, self.subgrid_cols),
+        ] {
+            options.check_capacity(rows, cols).with_context(|| {
+                format!(
+                    "{} too large for the label alphabet: {}x{} = {} cells",
+                    name,
+                    rows,
+                    cols,
+                    rows * cols
+                )
+            })?;
+        }
+
         // Validate border width
         if self.border_width > 10 {
             return Err(anyhow!(
@@ -225,13 +753,14 @@ impl HyprGridConfig {
             ));
         }
 
-        // Validate border color format (basic check for hex color)
-        if !self.border_color.starts_with('#') ||
-           (self.border_color.len() != 7 && self.border_color.len() != 4) {
-            return Err(anyhow!(
-                "Invalid border_color: '{}'. Must be a hex color (e.g., #FFFFFF or #FFF).",
-                self.border_color
-            ));
+        self.theme.validate()?;
+        self.key_bindings()?;
+
+        // Validate each monitor's configuration
+        for (key, overrides) in &self.monitor {
+            self.with_override(overrides)
+                .validate()
+                .with_context(|| format!("Invalid settings in [monitor.\"{}\"]", key))?;
         }
 
         Ok(())
@@ -240,12 +769,13 @@ impl HyprGridConfig {
 
 /// Get the currently focused monitor from Hyprland
 ///
-/// This function runs `hyprctl monitors -j` to query Hyprland for all monitors
-/// and returns the one that is currently focused.
+/// This function queries Hyprland for all monitors (over its IPC socket, or
+/// via `hyprctl monitors -j` as a fallback) and returns the one that is
+/// currently focused.
 ///
 /// # Returns
 /// - `Ok(MonitorInfo)` with the focused monitor information
-/// - `Err` if hyprctl fails, JSON parsing fails, or no focused monitor is found
+/// - `Err` if Hyprland cannot be queried, JSON parsing fails, or no focused monitor is found
 ///
 /// # Example
 /// ```no_run
@@ -255,35 +785,37 @@ impl HyprGridConfig {
 /// println!("Active monitor: {} ({}x{})", monitor.name, monitor.width, monitor.height);
 /// ```
 pub fn get_active_monitor() -> Result<MonitorInfo> {
-    // Run hyprctl to get monitor information
-    let output = Command::new("hyprctl")
-        .args(["monitors", "-j"])
-        .output()
-        .context(
-            "Failed to execute 'hyprctl monitors -j'.\n\
-             Please ensure you are running Hyprland and hyprctl is installed."
-        )?;
+    let json = hyprland::monitors_json()?;
+    find_focused_monitor(&json)
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow!(
-            "hyprctl command failed with status: {}\n\
-             stderr: {}",
-            output.status,
-            stderr
-        ));
-    }
-
-    // Parse JSON output
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let monitors: Vec<MonitorInfo> = serde_json::from_str(&stdout)
-        .with_context(|| {
-            format!(
-                "Failed to parse JSON output from hyprctl.\n\
-                 Output was: {}",
-                stdout
-            )
-        })?;
+/// Get a monitor from Hyprland by its connector name (e.g., "DP-2")
+///
+/// This is used when a monitor is chosen on the command line instead of
+/// following the focus.
+///
+/// # Returns
+/// - `Ok(MonitorInfo)` with the monitor information
+/// - `Err` if Hyprland cannot be queried, JSON parsing fails, or no monitor has that name
+pub fn get_monitor(name: &str) -> Result<MonitorInfo> {
+    let json = hyprland::monitors_json()?;
+    find_named_monitor(&json, name)
+}
+
+/// Parse Hyprland's monitor JSON
+fn parse_monitors(json: &str) -> Result<Vec<MonitorInfo>> {
+    serde_json::from_str(json).with_context(|| {
+        format!(
+            "Failed to parse monitor JSON from Hyprland.\n\
+             Output was: {}",
+            json
+        )
+    })
+}
+
+/// Parse Hyprland's monitor JSON and return the focused monitor
+fn find_focused_monitor(json: &str) -> Result<MonitorInfo> {
+    let monitors = parse_monitors(json)?;
 
     // Find the focused monitor
     monitors
@@ -297,6 +829,24 @@ pub fn get_active_monitor() -> Result<MonitorInfo> {
         })
 }
 
+/// Parse Hyprland's monitor JSON and return the monitor called `name`
+fn find_named_monitor(json: &str, name: &str) -> Result<MonitorInfo> {
+    let monitors = parse_monitors(json)?;
+    let names: Vec<String> = monitors.iter().map(|m| m.name.clone()).collect();
+
+    monitors
+        .into_iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "No monitor named '{}'.\n\
+                 Connected monitors: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,8 +862,14 @@ mod tests {
     fn test_grid_dimensions_landscape() {
         let monitor = MonitorInfo {
             name: "DP-1".to_string(),
+            description: String::new(),
             width: 2560,
             height: 1440,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            transform: 0,
+            reserved: [0, 0, 0, 0],
             focused: true,
         };
 
@@ -328,8 +884,14 @@ mod tests {
     fn test_grid_dimensions_portrait() {
         let monitor = MonitorInfo {
             name: "DP-1".to_string(),
+            description: String::new(),
             width: 1080,
             height: 1920,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            transform: 0,
+            reserved: [0, 0, 0, 0],
             focused: true,
         };
 
@@ -345,8 +907,14 @@ mod tests {
     fn test_grid_dimensions_square() {
         let monitor = MonitorInfo {
             name: "DP-1".to_string(),
+            description: String::new(),
             width: 1920,
             height: 1920,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            transform: 0,
+            reserved: [0, 0, 0, 0],
             focused: true,
         };
 
@@ -355,4 +923,519 @@ mod tests {
         assert_eq!(grid.cols, 20);
         assert_eq!(grid.orientation, Orientation::Square);
     }
+
+    #[test]
+    fn test_logical_size_fractional_scale() {
+        let monitor = MonitorInfo {
+            name: "DP-1".to_string(),
+            description: String::new(),
+            width: 3840,
+            height: 2160,
+            x: 0,
+            y: 0,
+            scale: 1.5,
+            transform: 0,
+            reserved: [0, 0, 0, 0],
+            focused: true,
+        };
+
+        assert_eq!(monitor.logical_size(), (2560, 1440));
+    }
+
+    #[test]
+    fn test_logical_size_rotated_transform() {
+        let mut monitor = MonitorInfo {
+            name: "DP-1".to_string(),
+            description: String::new(),
+            width: 1920,
+            height: 1080,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            transform: 1,
+            reserved: [0, 0, 0, 0],
+            focused: true,
+        };
+
+        assert_eq!(monitor.logical_size(), (1080, 1920));
+
+        // 180 degrees keeps the original orientation
+        monitor.transform = 2;
+        assert_eq!(monitor.logical_size(), (1920, 1080));
+
+        // Flipped + 270 degrees rotates again
+        monitor.transform = 7;
+        assert_eq!(monitor.logical_size(), (1080, 1920));
+    }
+
+    #[test]
+    fn test_grid_dimensions_rotated_monitor() {
+        // Landscape mode, but rotated 90 degrees: displayed as portrait
+        let monitor = MonitorInfo {
+            name: "DP-2".to_string(),
+            description: String::new(),
+            width: 2560,
+            height: 1440,
+            x: 0,
+            y: 0,
+            scale: 1.25,
+            transform: 3,
+            reserved: [0, 0, 0, 0],
+            focused: true,
+        };
+
+        let grid = GridDimensions::calculate(10, 20, &monitor);
+        assert_eq!(grid.rows, 20);
+        assert_eq!(grid.cols, 10);
+        assert_eq!(grid.orientation, Orientation::Portrait);
+    }
+
+    #[test]
+    fn test_reserved_area_setting() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 10\ngrid_cols = 20").unwrap();
+        assert_eq!(config.reserved_area, ReservedArea::Ignore);
+
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nreserved_area = \"dedicated\"").unwrap();
+        assert_eq!(config.reserved_area, ReservedArea::Dedicated);
+
+        let invalid = toml::from_str::<HyprGridConfig>(
+            "grid_rows = 10\ngrid_cols = 20\nreserved_area = \"bars\"",
+        );
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_layer_shell_setting() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 10\ngrid_cols = 20").unwrap();
+        assert!(config.layer_shell);
+
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nlayer_shell = false").unwrap();
+        assert!(!config.layer_shell);
+    }
+
+    #[test]
+    fn test_theme_defaults() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 10\ngrid_cols = 20").unwrap();
+        assert_eq!(config.theme, Theme::default());
+        assert!(config.validate().is_ok());
+
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 10\ngrid_cols = 20\n\
+             [theme]\nfont_size = 18\nhighlight = \"#F00\"\nlabel_position = \"corner\"",
+        )
+        .unwrap();
+        assert_eq!(config.theme.font_size, Some(18));
+        assert_eq!(config.theme.highlight, Color::rgb(0xFF, 0, 0));
+        assert_eq!(config.theme.label_position, LabelPosition::Corner);
+        assert_eq!(config.theme.foreground, Color::rgb(0xFF, 0xFF, 0xFF));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_example_config_is_valid() {
+        let config: HyprGridConfig =
+            toml::from_str(include_str!("../hg_config.conf.example")).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.theme, Theme::default());
+        assert_eq!(config.key_bindings().unwrap(), KeyBindings::default());
+    }
+
+    #[test]
+    fn test_theme_validation() {
+        let invalid = |theme: &str| {
+            let config: HyprGridConfig =
+                toml::from_str(&format!("grid_rows = 10\ngrid_cols = 20\n[theme]\n{}", theme)).unwrap();
+            config.validate().unwrap_err().to_string()
+        };
+
+        assert!(invalid("font_size = 200").contains("theme.font_size"));
+        assert!(invalid("font_family = \" \"").contains("theme.font_family"));
+
+        let bad_position = toml::from_str::<HyprGridConfig>(
+            "grid_rows = 10\ngrid_cols = 20\n[theme]\nlabel_position = \"left\"",
+        );
+        assert!(bad_position.is_err());
+    }
+
+    #[test]
+    fn test_color_settings() {
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 10\ngrid_cols = 20\nborder_color = \"rgba(33ccffee)\"\n\
+             [theme]\nbackground = \"rgba(0, 0, 0, 0.5)\"\nhighlight = \"0xFFFF8800\"",
+        )
+        .unwrap();
+        assert_eq!(config.border_color, Color::rgba(0x33, 0xCC, 0xFF, 0xEE));
+        assert_eq!(config.theme.background, Color::rgba(0, 0, 0, 128));
+        assert_eq!(config.theme.highlight, Color::rgb(0xFF, 0x88, 0x00));
+
+        // Invalid colors are reported with the offending line
+        let error = |toml: &str| {
+            toml::from_str::<HyprGridConfig>(&format!("grid_rows = 10\ngrid_cols = 20\n{}", toml))
+                .unwrap_err()
+                .to_string()
+        };
+        let message = error("border_color = \"#GGGGGG\"");
+        assert!(message.contains("border_color"));
+        assert!(message.contains("'G' in color '#GGGGGG' is not a hex digit"));
+        assert!(error("[theme]\nbackground = \"black\"").contains("'black' is not a color"));
+
+        // 8-digit hex colors used to be rejected
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nborder_color = \"#FFFFFF80\"").unwrap();
+        assert_eq!(config.border_color.a, 0x80);
+    }
+
+    #[test]
+    fn test_subgrid_size_validation() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 10\ngrid_cols = 20").unwrap();
+        assert_eq!((config.subgrid_rows, config.subgrid_cols), (5, 5));
+        assert!(config.validate().is_ok());
+
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nsubgrid_rows = 1").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_label_options_default_qwerty() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 10\ngrid_cols = 20").unwrap();
+        let options = config.label_options().unwrap();
+        assert_eq!(options.alphabet, Alphabet::default());
+        assert_eq!(options.mode, LabelMode::Sequential);
+    }
+
+    #[test]
+    fn test_label_mode_setting() {
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 10\ngrid_cols = 20\nkeyboard_layout = \"dvorak\"\nlabel_mode = \"ergonomic\"",
+        )
+        .unwrap();
+        let options = config.label_options().unwrap();
+        assert_eq!(options.mode, LabelMode::Ergonomic);
+        assert_eq!(options.layout.name, "dvorak");
+
+        // Regional labels need the grid to fit in 26 blocks of 26 cells
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 27\ngrid_cols = 25\nlabel_mode = \"regional\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("Grid too large for the label alphabet"));
+        assert!(format!("{:#}", err).contains("regional labels"));
+
+        // Coordinate labels need a letter per row and per column
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 30\nlabel_mode = \"coordinates\"").unwrap();
+        assert!(format!("{:#}", config.validate().unwrap_err()).contains("coordinate labels"));
+
+        let result: Result<HyprGridConfig, _> =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nlabel_mode = \"random\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_label_options_layout_preset() {
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nkeyboard_layout = \"colemak\"").unwrap();
+        let options = config.label_options().unwrap();
+        assert_eq!(options.alphabet.letters()[..4], ['a', 'r', 's', 't']);
+
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 10\ngrid_cols = 20\nkeyboard_layout = \"azerty\"").unwrap();
+        let err = config.label_options().unwrap_err();
+        assert!(err.to_string().contains("Invalid keyboard_layout"));
+    }
+
+    #[test]
+    fn test_label_options_custom_alphabet() {
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 4\ngrid_cols = 4\nkeyboard_layout = \"dvorak\"\nlabel_alphabet = \"htns\"",
+        )
+        .unwrap();
+        let options = config.label_options().unwrap();
+        assert_eq!(options.alphabet.letters(), ['h', 't', 'n', 's']);
+        assert!(config.validate().is_ok());
+
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 4\ngrid_cols = 4\nlabel_alphabet = \"asdfa\"").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_alphabet_capacity_validation() {
+        // 4 letters make 16 two-letter labels, longer labels cover the rest
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 5\ngrid_cols = 4\nlabel_alphabet = \"asdf\"").unwrap();
+        assert!(config.validate().is_ok());
+
+        // ...but only up to 4^4 = 256 cells
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 16\ngrid_cols = 17\nlabel_alphabet = \"asdf\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("Grid too large for the label alphabet"));
+
+        // Dedicated reserved strips may add two rows and two columns
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 15\ngrid_cols = 15\nlabel_alphabet = \"asdf\"").unwrap();
+        assert!(config.validate().is_ok());
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 15\ngrid_cols = 15\nlabel_alphabet = \"asdf\"\nreserved_area = \"dedicated\"",
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("Grid with dedicated reserved strips too large"));
+        assert!(err.to_string().contains("17x17 = 289 cells"));
+
+        // The sub-grid must fit as well: 2^4 = 16 labels
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 4\ngrid_cols = 4\nlabel_alphabet = \"as\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("Sub-grid too large for the label alphabet"));
+    }
+
+    #[test]
+    fn test_config_search_order() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let files = |existing: &'static [&'static str]| {
+            move |path: &Path| existing.iter().any(|file| Path::new(file) == path)
+        };
+        let all = files(&[
+            "/flag.toml",
+            "/env.toml",
+            "/xdg/hypr/hg_config.conf",
+            "/xdg/hyprgrid/config.toml",
+            "/home/tron/.config/hypr/hg_config.conf",
+            "/home/tron/.config/hyprgrid/config.toml",
+        ]);
+        let vars = env(&[
+            ("HYPRGRID_CONFIG", "/env.toml"),
+            ("XDG_CONFIG_HOME", "/xdg"),
+            ("HOME", "/home/tron"),
+        ]);
+        let found = |path: &str| Some(PathBuf::from(path));
+
+        assert_eq!(find_config_path(Some(Path::new("/flag.toml")), vars, all).unwrap(), found("/flag.toml"));
+        assert_eq!(find_config_path(None, vars, all).unwrap(), found("/env.toml"));
+
+        let xdg = env(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/tron")]);
+        assert_eq!(find_config_path(None, xdg, all).unwrap(), found("/xdg/hypr/hg_config.conf"));
+        let only_new_name = files(&["/xdg/hyprgrid/config.toml", "/home/tron/.config/hypr/hg_config.conf"]);
+        assert_eq!(
+            find_config_path(None, xdg, only_new_name).unwrap(),
+            found("/xdg/hyprgrid/config.toml")
+        );
+
+        // Relative XDG_CONFIG_HOME is ignored, falling back to ~/.config
+        let relative = env(&[("XDG_CONFIG_HOME", "xdg"), ("HOME", "/home/tron")]);
+        let home_only = files(&["/home/tron/.config/hyprgrid/config.toml"]);
+        assert_eq!(
+            find_config_path(None, relative, home_only).unwrap(),
+            found("/home/tron/.config/hyprgrid/config.toml")
+        );
+
+        // No file anywhere, or not even HOME: use the defaults
+        assert_eq!(find_config_path(None, xdg, files(&[])).unwrap(), None);
+        assert_eq!(find_config_path(None, env(&[]), all).unwrap(), None);
+    }
+
+    #[test]
+    fn test_explicit_config_must_exist() {
+        let no_env = |_: &str| None;
+        let err = find_config_path(Some(Path::new("/missing.toml")), no_env, |_: &Path| false).unwrap_err();
+        assert_eq!(err.to_string(), "Configuration file not found: /missing.toml (from --config)");
+
+        let env = |name: &str| (name == CONFIG_ENV).then(|| "/missing.toml".to_string());
+        let err = find_config_path(None, env, |_: &Path| false).unwrap_err();
+        assert!(err.to_string().contains("(from HYPRGRID_CONFIG)"));
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = HyprGridConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!((config.grid_rows, config.grid_cols), (10, 20));
+
+        // An empty file gives the same configuration
+        let empty: HyprGridConfig = toml::from_str("").unwrap();
+        assert_eq!((empty.grid_rows, empty.grid_cols), (10, 20));
+        assert_eq!(empty.theme, config.theme);
+    }
+
+    #[test]
+    fn test_load_from_file() {
+        let path = std::env::temp_dir().join(format!("hyprgrid-test-{}.toml", std::process::id()));
+        fs::write(&path, "grid_rows = 6\ngrid_cols = 12\n").unwrap();
+        let config = HyprGridConfig::load_from(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(config.unwrap().grid_cols, 12);
+
+        let err = HyprGridConfig::load_from(&path).unwrap_err();
+        assert!(err.to_string().contains("Failed to read configuration file"));
+    }
+
+    #[test]
+    fn test_find_focused_monitor() {
+        let json = include_str!("../hyprctl_return_monitors.json");
+        let monitor = find_focused_monitor(json).unwrap();
+        assert_eq!(monitor.name, "HDMI-A-1");
+        assert_eq!(monitor.width, 1920);
+        assert_eq!(monitor.height, 1080);
+        assert_eq!((monitor.x, monitor.y), (0, 0));
+        assert_eq!(monitor.scale, 1.0);
+        assert_eq!(monitor.transform, 0);
+        assert_eq!(monitor.reserved, [0, 38, 0, 0]);
+    }
+
+    #[test]
+    fn test_monitor_negative_offset() {
+        let json = include_str!("../hyprctl_return_monitors.json");
+        let monitors: Vec<MonitorInfo> = serde_json::from_str(json).unwrap();
+        let dp1 = monitors.iter().find(|m| m.name == "DP-1").unwrap();
+        assert_eq!((dp1.x, dp1.y), (-1920, 0));
+        let dp2 = monitors.iter().find(|m| m.name == "DP-2").unwrap();
+        assert_eq!((dp2.x, dp2.y), (1920, 0));
+    }
+
+    #[test]
+    fn test_monitor_overrides() {
+        let config: HyprGridConfig = toml::from_str(
+            r##"
+            grid_rows = 10
+            grid_cols = 20
+
+            [theme]
+            highlight = "#F00"
+
+            [monitor."DP-2"]
+            grid_rows = 6
+            grid_cols = 12
+
+            [monitor."DP-2".theme]
+            font_size = 24
+
+            [monitor."AOC 27B30H"]
+            label_alphabet = "arstneio"
+
+            [monitor."LG Electronics"]
+            grid_rows = 5
+            "##,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let json = include_str!("../hyprctl_return_monitors.json");
+        let monitors: Vec<MonitorInfo> = serde_json::from_str(json).unwrap();
+        let monitor = |name: &str| monitors.iter().find(|m| m.name == name).unwrap();
+
+        // A name match wins over a description match
+        let dp2 = config.for_monitor(monitor("DP-2"));
+        assert_eq!(config.monitor_override(monitor("DP-2")).unwrap().0, "DP-2");
+        assert_eq!((dp2.grid_rows, dp2.grid_cols), (6, 12));
+        assert_eq!(dp2.theme.font_size, Some(24));
+        assert_eq!(dp2.theme.highlight, Color::rgb(0xFF, 0, 0));
+        assert!(dp2.monitor.is_empty());
+
+        // Descriptions match on a part such as make or model
+        let dp1 = config.for_monitor(monitor("DP-1"));
+        assert_eq!((dp1.grid_rows, dp1.grid_cols), (5, 20));
+        assert_eq!(dp1.theme, config.theme);
+
+        let hdmi = config.for_monitor(monitor("HDMI-A-1"));
+        assert_eq!((hdmi.grid_rows, hdmi.grid_cols), (10, 20));
+        assert_eq!(hdmi.label_options().unwrap().alphabet.len(), 8);
+
+        let mut other = monitor("DP-1").clone();
+        other.name = "eDP-1".to_string();
+        other.description = String::new();
+        assert!(config.monitor_override(&other).is_none());
+        assert_eq!(config.for_monitor(&other).grid_rows, 10);
+    }
+
+    #[test]
+    fn test_monitor_override_validation() {
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 10\ngrid_cols = 20\n[monitor.\"DP-1\"]\ngrid_rows = 60",
+        )
+        .unwrap();
+        let err = format!("{:#}", config.validate().unwrap_err());
+        assert!(err.contains("Invalid settings in [monitor.\"DP-1\"]"));
+        assert!(err.contains("Invalid grid_rows: 60"));
+
+        // Only grid size, labels and theme can be overridden
+        let unknown = toml::from_str::<HyprGridConfig>(
+            "grid_rows = 10\ngrid_cols = 20\n[monitor.\"DP-1\"]\nborder_width = 3",
+        );
+        assert!(unknown.unwrap_err().to_string().contains("unknown field `border_width`"));
+    }
+
+    #[test]
+    fn test_key_table() {
+        let config: HyprGridConfig = toml::from_str(
+            r#"
+            [keys]
+            space = "ctrl-click"
+            "!" = "shift-click"
+            "#,
+        )
+        .unwrap();
+        let bindings = config.key_bindings().unwrap();
+        assert_eq!(bindings.action(Key::Space), Some(Action::CtrlClick));
+        assert_eq!(bindings.action(Key::Char('!')), Some(Action::ShiftClick));
+        assert_eq!(bindings.action(Key::Enter), Some(Action::RightClick));
+
+        let error = |toml: &str| {
+            let config: HyprGridConfig = toml::from_str(toml).unwrap();
+            config.validate().unwrap_err().to_string()
+        };
+        assert!(error("[keys]\nf1 = \"move\"").contains("Invalid key in [keys]: 'f1'"));
+        assert!(error("[keys]\nx = \"move\"").contains("Letters are used to type labels"));
+
+        let unknown = toml::from_str::<HyprGridConfig>("[keys]\nspace = \"quadruple-click\"");
+        assert!(unknown.unwrap_err().to_string().contains("unknown action 'quadruple-click'"));
+    }
+
+    #[test]
+    fn test_find_named_monitor() {
+        let json = include_str!("../hyprctl_return_monitors.json");
+        let monitor = find_named_monitor(json, "DP-2").unwrap();
+        assert_eq!((monitor.x, monitor.y), (1920, 0));
+        assert!(!monitor.focused);
+
+        let err = find_named_monitor(json, "eDP-1").unwrap_err().to_string();
+        assert!(err.contains("No monitor named 'eDP-1'"));
+        assert!(err.contains("Connected monitors: "));
+        assert!(err.contains("DP-2"));
+    }
+
+    #[test]
+    fn test_grid_size_from_command_line() {
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 10\ngrid_cols = 20\n[monitor.\"DP-2\"]\ngrid_rows = 6",
+        )
+        .unwrap();
+        let json = include_str!("../hyprctl_return_monitors.json");
+        let dp2 = find_named_monitor(json, "DP-2").unwrap();
+
+        // Command-line values win over the monitor table
+        let resolved = config.for_monitor(&dp2).with_grid_size(Some(8), None).unwrap();
+        assert_eq!((resolved.grid_rows, resolved.grid_cols), (8, 20));
+        let resolved = config.for_monitor(&dp2).with_grid_size(None, Some(12)).unwrap();
+        assert_eq!((resolved.grid_rows, resolved.grid_cols), (6, 12));
+
+        let err = config.with_grid_size(Some(1), None).unwrap_err();
+        assert!(err.to_string().contains("Invalid grid_rows: 1"));
+    }
+
+    #[test]
+    fn test_find_focused_monitor_none_focused() {
+        let json = r#"[{"name": "DP-1", "width": 1920, "height": 1080, "focused": false}]"#;
+        let err = find_focused_monitor(json).unwrap_err();
+        assert!(err.to_string().contains("No focused monitor found"));
+    }
 }
@@ -0,0 +1,274 @@
+// Dump module for HyprGrid
+//
+// This module writes the computed grid of a monitor in a machine-readable
+// form, so scripts and tests can use exactly the cells the overlay would show
+// without parsing the human-readable report.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+
+use crate::config::{MonitorInfo, Orientation};
+use crate::grid::{CellCoordinates, Grid, GridCell};
+
+/// Output format of `hyprgrid dump`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// A single JSON document describing the monitor and every cell
+    #[default]
+    Json,
+    /// One CSV line per cell, after a header line
+    Csv,
+}
+
+impl DumpFormat {
+    /// Every format with the name used on the command line
+    const NAMES: [(&'static str, DumpFormat); 2] = [("json", DumpFormat::Json), ("csv", DumpFormat::Csv)];
+
+    /// Look up a format by its command-line name (e.g., "csv")
+    pub fn from_name(name: &str) -> Option<DumpFormat> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, format)| format)
+    }
+
+    /// Get the accepted format names, comma-separated
+    pub fn names() -> String {
+        Self::NAMES.map(|(name, _)| name).join(", ")
+    }
+}
+
+/// Columns of the CSV output
+const CSV_HEADER: &str = "monitor,orientation,label,x,y,width,height,center_x,center_y,row,col";
+
+/// The JSON document written by [`write_dump`]
+#[derive(Serialize)]
+struct GridDump<'a> {
+    monitor: MonitorDump<'a>,
+    orientation: String,
+    rows: u32,
+    cols: u32,
+    area: Rect,
+    cells: Vec<CellDump<'a>>,
+}
+
+/// The monitor, in the same logical coordinates as the cells
+#[derive(Serialize)]
+struct MonitorDump<'a> {
+    name: &'a str,
+    description: &'a str,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale: f64,
+    transform: u32,
+}
+
+#[derive(Serialize)]
+struct CellDump<'a> {
+    label: &'a str,
+    rect: Rect,
+    center: Point,
+    grid_position: Position,
+}
+
+#[derive(Serialize)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Serialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize)]
+struct Position {
+    row: u32,
+    col: u32,
+}
+
+impl From<&CellCoordinates> for Rect {
+    fn from(area: &CellCoordinates) -> Self {
+        Rect {
+            x: area.x,
+            y: area.y,
+            width: area.width,
+            height: area.height,
+        }
+    }
+}
+
+impl<'a> From<&'a GridCell> for CellDump<'a> {
+    fn from(cell: &'a GridCell) -> Self {
+        let (x, y) = cell.coordinates.center();
+        let (row, col) = cell.grid_position;
+        CellDump {
+            label: &cell.label,
+            rect: Rect::from(&cell.coordinates),
+            center: Point { x, y },
+            grid_position: Position { row, col },
+        }
+    }
+}
+
+/// Write the grid of a monitor as JSON or CSV
+///
+/// All coordinates are global logical coordinates, as used for clicking.
+/// The monitor size is its logical size, and the orientation is lowercase
+/// ("landscape", "portrait" or "square").
+///
+/// # Arguments
+/// * `out` - Where to write the dump (e.g., stdout)
+/// * `format` - JSON or CSV
+/// * `monitor` - The monitor the grid was built for
+/// * `orientation` - The orientation used to rotate the grid
+/// * `grid` - The grid to dump
+///
+/// # Example
+/// ```no_run
+/// use hyprgrid::dump::{write_dump, DumpFormat};
+///
+/// write_dump(&mut std::io::stdout(), DumpFormat::Csv, &monitor, orientation, &grid)?;
+/// ```
+pub fn write_dump(
+    out: &mut impl Write,
+    format: DumpFormat,
+    monitor: &MonitorInfo,
+    orientation: Orientation,
+    grid: &Grid,
+) -> Result<()> {
+    let orientation = orientation.as_str().to_lowercase();
+
+    match format {
+        DumpFormat::Json => {
+            let (width, height) = monitor.logical_size();
+            let dump = GridDump {
+                monitor: MonitorDump {
+                    name: &monitor.name,
+                    description: &monitor.description,
+                    x: monitor.x,
+                    y: monitor.y,
+                    width,
+                    height,
+                    scale: monitor.scale,
+                    transform: monitor.transform,
+                },
+                orientation,
+                rows: grid.rows,
+                cols: grid.cols,
+                area: Rect::from(&grid.area),
+                cells: grid.cells().map(CellDump::from).collect(),
+            };
+            serde_json::to_writer_pretty(&mut *out, &dump).context("Failed to write the grid as JSON")?;
+            writeln!(out)?;
+        }
+        DumpFormat::Csv => {
+            let monitor = csv_field(&monitor.name);
+            writeln!(out, "{}", CSV_HEADER)?;
+            for cell in grid.cells() {
+                let area = &cell.coordinates;
+                let (cx, cy) = area.center();
+                let (row, col) = cell.grid_position;
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{},{},{}",
+                    monitor, orientation, cell.label, area.x, area.y, area.width, area.height, cx, cy, row, col
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> MonitorInfo {
+        serde_json::from_str(
+            r#"{"name": "DP-2", "description": "LG Electronics", "width": 1200, "height": 800,
+                "x": 1920, "y": 0, "scale": 2.0, "focused": false}"#,
+        )
+        .unwrap()
+    }
+
+    fn grid() -> Grid {
+        let area = CellCoordinates {
+            x: 1920,
+            y: 0,
+            width: 600,
+            height: 400,
+        };
+        Grid::for_area(2, 3, area, &Default::default()).unwrap()
+    }
+
+    fn dump(format: DumpFormat) -> String {
+        let mut out = Vec::new();
+        write_dump(&mut out, format, &monitor(), Orientation::Landscape, &grid()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format_names() {
+        assert_eq!(DumpFormat::from_name("json"), Some(DumpFormat::Json));
+        assert_eq!(DumpFormat::from_name("CSV"), Some(DumpFormat::Csv));
+        assert_eq!(DumpFormat::from_name("xml"), None);
+        assert_eq!(DumpFormat::names(), "json, csv");
+    }
+
+    #[test]
+    fn test_json_dump() {
+        let json: serde_json::Value = serde_json::from_str(&dump(DumpFormat::Json)).unwrap();
+
+        assert_eq!(json["monitor"]["name"], "DP-2");
+        assert_eq!((json["monitor"]["width"].as_u64(), json["monitor"]["height"].as_u64()), (Some(600), Some(400)));
+        assert_eq!(json["orientation"], "landscape");
+        assert_eq!((json["rows"].as_u64(), json["cols"].as_u64()), (Some(2), Some(3)));
+
+        let cells = json["cells"].as_array().unwrap();
+        assert_eq!(cells.len(), 6);
+        let last = &cells[5];
+        assert_eq!(last["label"], grid().cell_at(1, 2).unwrap().label.as_str());
+        assert_eq!(
+            last["rect"],
+            serde_json::json!({"x": 2320, "y": 200, "width": 200, "height": 200})
+        );
+        assert_eq!(last["center"], serde_json::json!({"x": 2420, "y": 300}));
+        assert_eq!(last["grid_position"], serde_json::json!({"row": 1, "col": 2}));
+    }
+
+    #[test]
+    fn test_csv_dump() {
+        let csv = dump(DumpFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], CSV_HEADER);
+        let label = grid().cell_at(0, 1).unwrap().label.clone();
+        assert_eq!(lines[2], format!("DP-2,landscape,{},2120,0,200,200,2220,100,0,1", label));
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        assert_eq!(csv_field("DP-1"), "DP-1");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
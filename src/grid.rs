@@ -3,15 +3,168 @@
 // This module implements the mathematical logic to divide the screen into a grid
 // and calculate the coordinates for each cell.
 
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
 use std::collections::HashMap;
 
+use crate::layout::{self, Finger, KeyPosition, KeyboardLayout};
+
+/// The set of letters used to build cell labels, in priority order
+///
+/// Letters earlier in the alphabet are handed out first, so the most
+/// comfortable keys should come first (e.g., the home row).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+}
+
+impl Alphabet {
+    /// Create an alphabet from a string of letters
+    ///
+    /// Letters are lowercased. The alphabet must contain at least two
+    /// letters, only ASCII letters, and no letter more than once.
+    ///
+    /// # Returns
+    /// - `Ok(Alphabet)` if the string is a valid alphabet
+    /// - `Err` naming the offending character otherwise
+    pub fn new(letters: &str) -> Result<Self> {
+        let mut seen: Vec<char> = Vec::with_capacity(letters.len());
+
+        for (position, c) in letters.chars().enumerate() {
+            if !c.is_ascii_alphabetic() {
+                return Err(anyhow!(
+                    "Invalid character '{}' at position {} in label alphabet '{}'.\n\
+                     Only letters a-z are allowed.",
+                    c,
+                    position + 1,
+                    letters
+                ));
+            }
+
+            let c = c.to_ascii_lowercase();
+            if seen.contains(&c) {
+                return Err(anyhow!(
+                    "Duplicate letter '{}' in label alphabet '{}'.\n\
+                     Each letter may appear only once.",
+                    c,
+                    letters
+                ));
+            }
+            seen.push(c);
+        }
+
+        if seen.len() < 2 {
+            return Err(anyhow!(
+                "Label alphabet '{}' is too short. It must contain at least 2 letters.",
+                letters
+            ));
+        }
+
+        Ok(Alphabet { letters: seen })
+    }
+
+    /// Get the letters in priority order
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Get the number of letters
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+}
+
+impl Default for Alphabet {
+    /// The QWERTY home row alphabet: `asdfghjkl` followed by the other letters
+    fn default() -> Self {
+        Alphabet {
+            letters: layout::QWERTY.home_row_alphabet().chars().collect(),
+        }
+    }
+}
+
+/// How labels are assigned to cells
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelMode {
+    /// Row-major order: the first cells get the first labels (`aa`, `as`, ...)
+    #[default]
+    Sequential,
+    /// The easiest labels to type go to the cells nearest the center
+    Ergonomic,
+    /// The first letter picks a block of the screen, the second a cell in it
+    Regional,
+    /// The first letter picks a row, the second a column; rulers along the
+    /// edges replace per-cell labels
+    Coordinates,
+}
+
+/// Options controlling how grid cells are labeled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelOptions {
+    /// Letters used to build labels
+    pub alphabet: Alphabet,
+    /// How labels are assigned to cells
+    pub mode: LabelMode,
+    /// Physical key positions, used to score typing effort
+    pub layout: &'static KeyboardLayout,
+}
+
+impl LabelOptions {
+    /// Check that a grid of the given size can be labeled with these options
+    ///
+    /// # Returns
+    /// - `Ok(())` if every cell can get a label
+    /// - `Err` explaining the limit otherwise
+    pub fn check_capacity(&self, rows: u32, cols: u32) -> Result<()> {
+        let letters = self.alphabet.len();
+        match self.mode {
+            LabelMode::Sequential | LabelMode::Ergonomic => {
+                let cells = rows as usize * cols as usize;
+                if cells > label_capacity(letters) {
+                    return Err(anyhow!(
+                        "{} letters only make {} labels of up to {} letters.",
+                        letters,
+                        label_capacity(letters),
+                        MAX_LABEL_LENGTH
+                    ));
+                }
+            }
+            LabelMode::Regional => {
+                if regional_block_size(rows, cols, letters).is_none() {
+                    return Err(regional_capacity_error(rows, cols, letters));
+                }
+            }
+            LabelMode::Coordinates => {
+                if rows.max(cols) as usize > letters {
+                    return Err(coordinate_capacity_error(rows, cols, letters));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for LabelOptions {
+    fn default() -> Self {
+        LabelOptions {
+            alphabet: Alphabet::default(),
+            mode: LabelMode::default(),
+            layout: &layout::QWERTY,
+        }
+    }
+}
+
 /// Represents the coordinates and dimensions of a grid cell
+///
+/// Coordinates are global compositor coordinates, so they can be negative for
+/// monitors placed left of or above the origin.
 #[derive(Debug, Clone, PartialEq)]
 pub struct CellCoordinates {
     /// X coordinate of the top-left corner
-    pub x: u32,
+    pub x: i32,
     /// Y coordinate of the top-left corner
-    pub y: u32,
+    pub y: i32,
     /// Width of the cell in pixels
     pub width: u32,
     /// Height of the cell in pixels
@@ -20,41 +173,104 @@ pub struct CellCoordinates {
 
 impl CellCoordinates {
     /// Calculate the center point of the cell (for mouse click target)
-    pub fn center(&self) -> (u32, u32) {
+    pub fn center(&self) -> (i32, i32) {
         (
-            self.x + self.width / 2,
-            self.y + self.height / 2,
+            self.x + (self.width / 2) as i32,
+            self.y + (self.height / 2) as i32,
         )
     }
+
+    /// Shrink the rectangle by the given amount at each edge
+    ///
+    /// # Arguments
+    /// * `insets` - Pixels to remove at each edge: `[left, top, right, bottom]`
+    ///
+    /// Insets larger than the rectangle are clamped, leaving an empty area.
+    pub fn inset(&self, insets: [u32; 4]) -> CellCoordinates {
+        let [left, top, right, bottom] = insets;
+        let left = left.min(self.width);
+        let top = top.min(self.height);
+        let right = right.min(self.width - left);
+        let bottom = bottom.min(self.height - top);
+
+        CellCoordinates {
+            x: self.x + left as i32,
+            y: self.y + top as i32,
+            width: self.width - left - right,
+            height: self.height - top - bottom,
+        }
+    }
 }
 
 /// Represents a single cell in the grid
 #[derive(Debug, Clone)]
 pub struct GridCell {
-    /// Two-letter label for this cell (e.g., "aa", "ab", "sd")
-    #[allow(dead_code)]
+    /// Letter label for this cell (e.g., "aa", "ab", "sd")
     pub label: String,
     /// Coordinates and dimensions of the cell
     pub coordinates: CellCoordinates,
     /// Grid position (row, column)
-    #[allow(dead_code)]
     pub grid_position: (u32, u32),
 }
 
+/// A letter naming a whole row or column, drawn along the edge of the screen
+/// in coordinate mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruler {
+    /// The letter to type for this row or column
+    pub letter: char,
+    /// Global coordinate where the row (y) or column (x) starts
+    pub start: i32,
+    /// Height of the row or width of the column in pixels
+    pub length: u32,
+}
+
+/// How a typed label is resolved to a grid position
+#[derive(Debug, Clone)]
+enum LabelScheme {
+    /// Every cell has its own label, looked up in a table
+    PerCell(HashMap<String, (u32, u32)>),
+    /// A label is a row letter followed by a column letter
+    Coordinates(Vec<char>),
+}
+
+impl LabelScheme {
+    /// Find the grid position a label refers to
+    ///
+    /// The position is not bounds-checked; [`Grid::cell_at`] does that.
+    fn resolve(&self, label: &str) -> Option<(u32, u32)> {
+        match self {
+            LabelScheme::PerCell(positions) => positions.get(label).copied(),
+            LabelScheme::Coordinates(letters) => {
+                let index = |letter: char| {
+                    letters.iter().position(|&c| c == letter).map(|i| i as u32)
+                };
+                let mut chars = label.chars();
+                let (row, col) = (chars.next()?, chars.next()?);
+                if chars.next().is_some() {
+                    return None;
+                }
+                Some((index(row)?, index(col)?))
+            }
+        }
+    }
+}
+
 /// The complete grid structure with cell mappings
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Grid {
     /// Number of rows in the grid
     pub rows: u32,
     /// Number of columns in the grid
     pub cols: u32,
-    /// Monitor dimensions
-    #[allow(dead_code)]
-    pub monitor_width: u32,
-    #[allow(dead_code)]
-    pub monitor_height: u32,
-    /// HashMap for efficient lookup: letter pair -> GridCell
-    cells: HashMap<String, GridCell>,
+    /// Area covered by the grid, in global coordinates
+    pub area: CellCoordinates,
+    /// Labeling options, reused when the grid is subdivided
+    options: LabelOptions,
+    /// How labels map to grid positions
+    scheme: LabelScheme,
+    /// All cells in row-major order, indexed by grid position
+    cells: Vec<GridCell>,
 }
 
 impl Grid {
@@ -67,68 +283,236 @@ impl Grid {
     /// * `monitor_height` - Height of the monitor in pixels
     ///
     /// # Returns
-    /// A complete Grid with all cells calculated and labeled
+    /// - `Ok(Grid)` with all cells calculated and labeled
+    /// - `Err` if the grid has too many cells to label
     ///
     /// # Example
     /// ```no_run
     /// use hyprgrid::grid::Grid;
     ///
-    /// let grid = Grid::new(10, 20, 1920, 1080);
+    /// let grid = Grid::new(10, 20, 1920, 1080)?;
     /// if let Some(cell) = grid.get_cell("aa") {
     ///     let (x, y) = cell.coordinates.center();
     ///     println!("Cell 'aa' center: ({}, {})", x, y);
     /// }
     /// ```
-    pub fn new(rows: u32, cols: u32, monitor_width: u32, monitor_height: u32) -> Self {
-        let mut cells = HashMap::new();
-        let labels = generate_letter_labels(rows, cols);
+    #[allow(dead_code)]
+    pub fn new(rows: u32, cols: u32, monitor_width: u32, monitor_height: u32) -> Result<Self> {
+        let area = CellCoordinates {
+            x: 0,
+            y: 0,
+            width: monitor_width,
+            height: monitor_height,
+        };
+        Self::for_area(rows, cols, area, &LabelOptions::default())
+    }
 
-        let cell_width = monitor_width / cols;
-        let cell_height = monitor_height / rows;
+    /// Create a new grid covering an arbitrary area
+    ///
+    /// Cell coordinates are offset by the area's position, so passing a
+    /// monitor's layout position yields cells in global compositor coordinates.
+    ///
+    /// # Arguments
+    /// * `rows` - Number of rows in the grid
+    /// * `cols` - Number of columns in the grid
+    /// * `area` - The rectangle to divide (e.g., a monitor at its `x`/`y` offset)
+    /// * `options` - How to label the cells
+    pub fn for_area(
+        rows: u32,
+        cols: u32,
+        area: CellCoordinates,
+        options: &LabelOptions,
+    ) -> Result<Self> {
+        let row_spans = split_span(area.y, area.height, rows);
+        let col_spans = split_span(area.x, area.width, cols);
+        Self::from_spans(area, &row_spans, &col_spans, options)
+    }
 
-        let mut label_index = 0;
+    /// Create a grid whose reserved edge strips get their own cells
+    ///
+    /// The usable part of `area` (inside the reserved strips) is divided into
+    /// `rows` × `cols` cells as usual. Each non-empty reserved strip (e.g., a
+    /// status bar) adds one extra row or column of cells spanning exactly that
+    /// strip, so the bar can be targeted precisely.
+    ///
+    /// # Arguments
+    /// * `rows` - Number of rows in the usable area
+    /// * `cols` - Number of columns in the usable area
+    /// * `area` - The full rectangle, including reserved strips
+    /// * `reserved` - Reserved pixels at each edge: `[left, top, right, bottom]`
+    /// * `options` - How to label the cells
+    pub fn with_reserved_strips(
+        rows: u32,
+        cols: u32,
+        area: CellCoordinates,
+        reserved: [u32; 4],
+        options: &LabelOptions,
+    ) -> Result<Self> {
+        let usable = area.inset(reserved);
+        let [left, top, right, bottom] = [
+            usable.x - area.x,
+            usable.y - area.y,
+            (area.x + area.width as i32) - (usable.x + usable.width as i32),
+            (area.y + area.height as i32) - (usable.y + usable.height as i32),
+        ];
 
-        // Generate cells in row-major order (left to right, top to bottom)
-        for row in 0..rows {
-            for col in 0..cols {
-                let label = labels[label_index].clone();
-                label_index += 1;
+        let mut row_spans = Vec::new();
+        if top > 0 {
+            row_spans.push((area.y, top as u32));
+        }
+        row_spans.extend(split_span(usable.y, usable.height, rows));
+        if bottom > 0 {
+            row_spans.push((usable.y + usable.height as i32, bottom as u32));
+        }
+
+        let mut col_spans = Vec::new();
+        if left > 0 {
+            col_spans.push((area.x, left as u32));
+        }
+        col_spans.extend(split_span(usable.x, usable.width, cols));
+        if right > 0 {
+            col_spans.push((usable.x + usable.width as i32, right as u32));
+        }
+
+        Self::from_spans(area, &row_spans, &col_spans, options)
+    }
+
+    /// Build a grid from explicit row and column spans
+    ///
+    /// Each span is a `(start, length)` pair in global coordinates. Cells are
+    /// generated in row-major order (left to right, top to bottom) and labeled
+    /// according to `options.mode`.
+    fn from_spans(
+        area: CellCoordinates,
+        row_spans: &[(i32, u32)],
+        col_spans: &[(i32, u32)],
+        options: &LabelOptions,
+    ) -> Result<Self> {
+        let rows = row_spans.len() as u32;
+        let cols = col_spans.len() as u32;
+
+        let labels = match options.mode {
+            LabelMode::Sequential => generate_letter_labels(rows, cols, &options.alphabet)?,
+            LabelMode::Ergonomic => assign_by_effort(
+                generate_ergonomic_labels(rows, cols, &options.alphabet, options.layout)?,
+                &area,
+                row_spans,
+                col_spans,
+                options.layout,
+            ),
+            LabelMode::Regional => generate_regional_labels(rows, cols, &options.alphabet)?,
+            LabelMode::Coordinates => generate_coordinate_labels(rows, cols, &options.alphabet)?,
+        };
+
+        let mut labels = labels.into_iter();
+        let mut cells = Vec::with_capacity((rows * cols) as usize);
 
+        // Generate cells in row-major order (left to right, top to bottom)
+        for (row, &(y, height)) in row_spans.iter().enumerate() {
+            for (col, &(x, width)) in col_spans.iter().enumerate() {
                 let coordinates = CellCoordinates {
-                    x: col * cell_width,
-                    y: row * cell_height,
-                    width: cell_width,
-                    height: cell_height,
+                    x,
+                    y,
+                    width,
+                    height,
                 };
 
-                let cell = GridCell {
-                    label: label.clone(),
+                cells.push(GridCell {
+                    label: labels.next().expect("one label is generated per cell"),
                     coordinates,
-                    grid_position: (row, col),
-                };
-
-                cells.insert(label, cell);
+                    grid_position: (row as u32, col as u32),
+                });
             }
         }
 
-        Grid {
+        let scheme = match options.mode {
+            LabelMode::Coordinates => LabelScheme::Coordinates(options.alphabet.letters().to_vec()),
+            _ => LabelScheme::PerCell(
+                cells
+                    .iter()
+                    .map(|cell| (cell.label.clone(), cell.grid_position))
+                    .collect(),
+            ),
+        };
+
+        Ok(Grid {
             rows,
             cols,
-            monitor_width,
-            monitor_height,
+            area,
+            options: options.clone(),
+            scheme,
             cells,
-        }
+        })
     }
 
     /// Get a cell by its letter label
     ///
     /// # Arguments
-    /// * `label` - The two-letter label (e.g., "aa", "sd")
+    /// * `label` - The cell label (e.g., "aa", "sd")
     ///
     /// # Returns
     /// `Some(&GridCell)` if the label exists, `None` otherwise
     pub fn get_cell(&self, label: &str) -> Option<&GridCell> {
-        self.cells.get(label)
+        let (row, col) = self.scheme.resolve(label)?;
+        self.cell_at(row, col)
+    }
+
+    /// Subdivide a cell into a new, independently labeled grid
+    ///
+    /// This is the refinement stage used for pixel-precise targeting: after a
+    /// cell is picked, its rectangle becomes the area of a finer grid. The
+    /// result is an ordinary [`Grid`] in global coordinates, so it can be
+    /// subdivided again to any depth.
+    ///
+    /// # Arguments
+    /// * `label` - The label of the cell to subdivide
+    /// * `rows` - Number of rows in the sub-grid
+    /// * `cols` - Number of columns in the sub-grid
+    ///
+    /// # Returns
+    /// - `Ok(Grid)` covering exactly the cell's rectangle
+    /// - `Err` if the label does not exist, the cell is a single pixel, or
+    ///   the sub-grid has too many cells to label
+    ///
+    /// Rows and columns are clamped to the cell's height and width, so small
+    /// cells are split into one-pixel slices rather than empty cells.
+    pub fn subdivide(&self, label: &str, rows: u32, cols: u32) -> Result<Grid> {
+        let area = self
+            .get_cell(label)
+            .ok_or_else(|| anyhow!("No cell is labeled '{}'", label))?
+            .coordinates
+            .clone();
+
+        let rows = rows.min(area.height).max(1);
+        let cols = cols.min(area.width).max(1);
+        if rows * cols < 2 {
+            return Err(anyhow!("Cell '{}' is a single pixel and cannot be refined", label));
+        }
+
+        Grid::for_area(rows, cols, area, &self.options)
+            .with_context(|| format!("Cannot refine cell '{}'", label))
+    }
+
+    /// Get the label mode the grid was built with
+    pub fn label_mode(&self) -> LabelMode {
+        self.options.mode
+    }
+
+    /// Get the cell at a grid position
+    ///
+    /// # Arguments
+    /// * `row` - Zero-based row index
+    /// * `col` - Zero-based column index
+    pub fn cell_at(&self, row: u32, col: u32) -> Option<&GridCell> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        self.cells.get((row * self.cols + col) as usize)
+    }
+
+    /// Iterate over all cells in row-major order
+    pub fn cells(&self) -> impl Iterator<Item = &GridCell> {
+        self.cells.iter()
     }
 
     /// Get the total number of cells in the grid
@@ -139,67 +523,487 @@ impl Grid {
     /// Get all cell labels in a sorted order
     #[allow(dead_code)]
     pub fn get_all_labels(&self) -> Vec<String> {
-        let mut labels: Vec<String> = self.cells.keys().cloned().collect();
+        let mut labels: Vec<String> = self.cells.iter().map(|cell| cell.label.clone()).collect();
         labels.sort();
         labels
     }
 
     /// Check if a label exists in the grid
-    #[allow(dead_code)]
     pub fn has_label(&self, label: &str) -> bool {
-        self.cells.contains_key(label)
+        self.get_cell(label).is_some()
+    }
+
+    /// Check if any label in the grid starts with the given prefix
+    ///
+    /// Used by the input state machine to reject keystrokes that cannot lead
+    /// to a valid cell.
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.cells_with_prefix(prefix).next().is_some()
+    }
+
+    /// Iterate over the cells whose label starts with a prefix, in row-major
+    /// order
+    ///
+    /// This is what is still reachable after typing `prefix`: the overlay
+    /// dims every other cell, and a single match means the selection is
+    /// unambiguous.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hyprgrid::grid::Grid;
+    ///
+    /// let grid = Grid::new(10, 20, 1920, 1080).unwrap();
+    /// let candidates: Vec<_> = grid.cells_with_prefix("a").collect();
+    /// assert!(candidates.iter().all(|cell| cell.label.starts_with('a')));
+    /// ```
+    pub fn cells_with_prefix(&self, prefix: &str) -> impl Iterator<Item = &GridCell> + '_ {
+        let prefix = prefix.to_string();
+        self.cells
+            .iter()
+            .filter(move |cell| cell.label.starts_with(&prefix))
+    }
+
+    /// Get the bounding rectangle of all cells whose label starts with a prefix
+    ///
+    /// After the first keystroke this is the part of the screen that is still
+    /// reachable, so the overlay can highlight it. In regional mode it is
+    /// exactly the block chosen by the first letter.
+    ///
+    /// # Returns
+    /// `Some(CellCoordinates)` covering every matching cell, or `None` if no
+    /// label starts with `prefix`
+    pub fn prefix_bounds(&self, prefix: &str) -> Option<CellCoordinates> {
+        let mut matching = self.cells_with_prefix(prefix).map(|cell| &cell.coordinates);
+
+        let first = matching.next()?;
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.x + first.width as i32, first.y + first.height as i32);
+        for cell in matching {
+            left = left.min(cell.x);
+            top = top.min(cell.y);
+            right = right.max(cell.x + cell.width as i32);
+            bottom = bottom.max(cell.y + cell.height as i32);
+        }
+
+        Some(CellCoordinates {
+            x: left,
+            y: top,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+    }
+
+    /// Get the row and column rulers of a coordinate-mode grid
+    ///
+    /// In coordinate mode the overlay draws one letter per row along the left
+    /// edge and one letter per column along the top edge, instead of a label
+    /// in every cell.
+    ///
+    /// # Returns
+    /// `Some((rows, columns))` in coordinate mode, `None` in the other modes
+    pub fn rulers(&self) -> Option<(Vec<Ruler>, Vec<Ruler>)> {
+        if !matches!(self.scheme, LabelScheme::Coordinates(_)) {
+            return None;
+        }
+
+        let letter = |cell: &GridCell, index: usize| cell.label.chars().nth(index);
+        let mut rows = Vec::new();
+        let mut columns = Vec::new();
+        for cell in &self.cells {
+            let (row, col) = cell.grid_position;
+            if col == 0 {
+                rows.push(Ruler {
+                    letter: letter(cell, 0)?,
+                    start: cell.coordinates.y,
+                    length: cell.coordinates.height,
+                });
+            }
+            if row == 0 {
+                columns.push(Ruler {
+                    letter: letter(cell, 1)?,
+                    start: cell.coordinates.x,
+                    length: cell.coordinates.width,
+                });
+            }
+        }
+
+        Some((rows, columns))
+    }
+}
+
+/// Split a one-dimensional span into `count` consecutive pieces
+///
+/// The pieces tile the span exactly: when `length` is not divisible by
+/// `count`, the remainder pixels are spread evenly across the pieces, so
+/// sizes differ by at most one pixel and nothing is left uncovered.
+///
+/// # Returns
+/// A `(start, length)` pair for each piece, in order
+fn split_span(start: i32, length: u32, count: u32) -> Vec<(i32, u32)> {
+    let boundary = |i: u32| (u64::from(i) * u64::from(length) / u64::from(count)) as u32;
+    (0..count)
+        .map(|i| (start + boundary(i) as i32, boundary(i + 1) - boundary(i)))
+        .collect()
+}
+
+/// Score how hard a label is to type (lower is easier)
+///
+/// The model adds up a cost for every key and for every transition between
+/// consecutive keys:
+/// - Keys cost more off the home row (the bottom row most), on weak fingers
+///   (pinky, then ring) and in the inner columns that need an index stretch
+/// - Alternating hands is free
+/// - Staying on the same hand costs a little, more for each row jumped
+/// - Using the same finger for two different keys costs the most, again more
+///   for each row jumped; repeating the same key is cheap
+///
+/// Letters missing from the layout are scored as the worst possible key.
+///
+/// # Arguments
+/// * `layout` - Where the letters sit on the keyboard
+/// * `label` - The letter sequence to score
+///
+/// # Example
+/// ```no_run
+/// use hyprgrid::grid::sequence_effort;
+/// use hyprgrid::layout::QWERTY;
+///
+/// // "fj" alternates hands on the home row, "ed" is a same-finger row jump
+/// assert!(sequence_effort(&QWERTY, "fj") < sequence_effort(&QWERTY, "ed"));
+/// ```
+pub fn sequence_effort(layout: &KeyboardLayout, label: &str) -> u32 {
+    const ROW_COST: [u32; 3] = [2, 0, 3];
+    const WORST_KEY: u32 = 10;
+
+    let positions: Vec<Option<KeyPosition>> = label.chars().map(|c| layout.position(c)).collect();
+
+    let key_cost = |key: &Option<KeyPosition>| match key {
+        Some(key) => {
+            let finger = match key.finger() {
+                Finger::Pinky => 2,
+                Finger::Ring => 1,
+                Finger::Middle | Finger::Index => 0,
+            };
+            ROW_COST[key.row] + finger + u32::from(key.is_stretch())
+        }
+        None => WORST_KEY,
+    };
+
+    let transition_cost = |pair: &[Option<KeyPosition>]| match (pair[0], pair[1]) {
+        (Some(a), Some(b)) => {
+            let row_jump = a.row.abs_diff(b.row) as u32;
+            if a.hand() != b.hand() {
+                0
+            } else if a == b {
+                1
+            } else if a.finger() == b.finger() {
+                6 + 2 * row_jump
+            } else {
+                2 + row_jump
+            }
+        }
+        _ => WORST_KEY,
+    };
+
+    positions.iter().map(key_cost).sum::<u32>()
+        + positions.windows(2).map(transition_cost).sum::<u32>()
+}
+
+/// Reorder labels so the easiest ones land on the most used cells
+///
+/// The labels come from [`generate_ergonomic_labels`], which picked the
+/// easiest ones; this decides which cell gets which.
+///
+/// Cells are ranked by the distance of their center to the center of `area`,
+/// where pointer targets tend to cluster. Labels are ranked by length, then by
+/// [`sequence_effort`]. The n-th easiest label goes to the n-th most central
+/// cell; ties keep row-major order, so the result is deterministic.
+///
+/// # Returns
+/// The same labels, in row-major cell order
+fn assign_by_effort(
+    labels: Vec<String>,
+    area: &CellCoordinates,
+    row_spans: &[(i32, u32)],
+    col_spans: &[(i32, u32)],
+    layout: &KeyboardLayout,
+) -> Vec<String> {
+    // Work in doubled coordinates to keep span centers exact
+    let center_x = 2 * area.x as i64 + area.width as i64;
+    let center_y = 2 * area.y as i64 + area.height as i64;
+
+    let mut cells: Vec<(i64, usize)> = Vec::with_capacity(labels.len());
+    for &(y, height) in row_spans {
+        for &(x, width) in col_spans {
+            let dx = 2 * x as i64 + width as i64 - center_x;
+            let dy = 2 * y as i64 + height as i64 - center_y;
+            cells.push((dx * dx + dy * dy, cells.len()));
+        }
+    }
+    cells.sort();
+
+    let mut ranked: Vec<(usize, u32, String)> = labels
+        .into_iter()
+        .map(|label| (label.len(), sequence_effort(layout, &label), label))
+        .collect();
+    ranked.sort_by_key(|(length, effort, _)| (*length, *effort));
+
+    let mut assigned = vec![String::new(); cells.len()];
+    for ((_, cell_index), (_, _, label)) in cells.into_iter().zip(ranked) {
+        assigned[cell_index] = label;
     }
+    assigned
+}
+
+/// Shortest label length handed out, even when fewer letters would suffice
+const MIN_LABEL_LENGTH: u32 = 2;
+
+/// Longest label length allowed; larger grids are rejected
+pub const MAX_LABEL_LENGTH: u32 = 4;
+
+/// Get the maximum number of cells that can be labeled with an alphabet
+///
+/// # Arguments
+/// * `alphabet_len` - Number of letters in the alphabet
+pub fn label_capacity(alphabet_len: usize) -> usize {
+    alphabet_len.saturating_pow(MAX_LABEL_LENGTH)
 }
 
 /// Generate letter labels using home row priority
 ///
-/// This function creates two-letter labels for grid cells using a home row priority
-/// ordering. The home row letters (asdfghjkl) are used first for better ergonomics.
+/// This function creates prefix-free labels for grid cells from the given
+/// alphabet: no label is the beginning of another, so a label is complete as
+/// soon as it is typed. Labels are as short as possible. Every cell gets two
+/// letters when the alphabet has enough pairs; otherwise the last pairs are
+/// extended with a third letter (and so on), just enough to cover all cells.
+///
+/// Letters are used in alphabet order, so with the default QWERTY alphabet
+/// the home row letters (asdfghjkl) are used first for better ergonomics, and
+/// the earliest cells keep the shortest labels.
 ///
 /// # Arguments
 /// * `rows` - Number of rows in the grid
 /// * `cols` - Number of columns in the grid
+/// * `alphabet` - Letters to build labels from, in priority order
 ///
 /// # Returns
-/// A vector of letter labels in row-major order
+/// - `Ok(Vec<String>)` with labels in row-major order
+/// - `Err` if the cells would need labels longer than [`MAX_LABEL_LENGTH`]
 ///
-/// # Letter Priority
+/// # Letter Priority (default alphabet)
 /// Home row letters (9 letters): a, s, d, f, g, h, j, k, l
 /// Extended set (remaining 17 letters): b, c, e, i, m, n, o, p, q, r, t, u, v, w, x, y, z
+fn generate_letter_labels(rows: u32, cols: u32, alphabet: &Alphabet) -> Result<Vec<String>> {
+    let total_cells = rows as usize * cols as usize;
+    let letter_set = alphabet.letters();
+    let (length, kept, extended) = plan_label_lengths(total_cells, letter_set.len())?;
+
+    let mut labels: Vec<String> = (0..kept).map(|i| code(letter_set, i, length)).collect();
+    'extend: for i in kept..kept + extended {
+        let prefix = code(letter_set, i, length);
+        for letter in letter_set {
+            if labels.len() == total_cells {
+                break 'extend;
+            }
+            labels.push(format!("{}{}", prefix, letter));
+        }
+    }
+
+    Ok(labels)
+}
+
+/// Generate the easiest prefix-free labels for the ergonomic mode
+///
+/// The label set has the same shape as [`generate_letter_labels`] (as many
+/// labels of the shortest length as possible), but the labels are chosen
+/// from the whole code space by [`sequence_effort`] instead of alphabet
+/// order. The codes that add the least effort when turned into a letter
+/// longer are extended, the easiest of their extensions fill the remaining
+/// cells, and every other code is kept as it is. Ties keep alphabet order.
+///
+/// # Returns
+/// - `Ok(Vec<String>)` with labels in no particular cell order (see
+///   [`assign_by_effort`])
+/// - `Err` if the cells would need labels longer than [`MAX_LABEL_LENGTH`]
+fn generate_ergonomic_labels(
+    rows: u32,
+    cols: u32,
+    alphabet: &Alphabet,
+    layout: &KeyboardLayout,
+) -> Result<Vec<String>> {
+    let total_cells = rows as usize * cols as usize;
+    let letter_set = alphabet.letters();
+    let (length, kept, extended) = plan_label_lengths(total_cells, letter_set.len())?;
+
+    let effort = |code: &str| sequence_effort(layout, code);
+    let extend = |prefix: &str| -> Vec<String> {
+        letter_set.iter().map(|letter| format!("{}{}", prefix, letter)).collect()
+    };
+    // A stable sort keeps alphabet order among equally easy codes
+    let by_effort = |mut codes: Vec<String>| {
+        codes.sort_by_cached_key(|code| effort(code));
+        codes
+    };
+
+    let all_codes: Vec<String> = (0..letter_set.len().pow(length)).map(|i| code(letter_set, i, length)).collect();
+    if extended == 0 {
+        return Ok(by_effort(all_codes).into_iter().take(kept).collect());
+    }
+
+    // Extending a code trades its own effort for that of its extensions
+    let mut prefixes = all_codes;
+    prefixes.sort_by_cached_key(|prefix| {
+        let added: u32 = extend(prefix).iter().map(|code| effort(code)).sum();
+        added as i64 - effort(prefix) as i64
+    });
+    let mut codes = prefixes.split_off(extended);
+
+    let extensions = prefixes.iter().flat_map(|prefix| extend(prefix)).collect();
+    codes.extend(by_effort(extensions).into_iter().take(total_cells - kept));
+
+    Ok(codes)
+}
+
+/// The code with the given index among all codes of a fixed length
+///
+/// Codes are numbered in alphabet order: with "asd", 0 is "aa", 1 is "as".
+fn code(letters: &[char], index: usize, length: u32) -> String {
+    let base = letters.len();
+    (0..length)
+        .rev()
+        .map(|digit| letters[index / base.pow(digit) % base])
+        .collect()
+}
+
+/// Plan the lengths of a prefix-free label set covering `total_cells` cells
 ///
-/// # Panics
-/// Panics if the number of cells exceeds 676 (26×26 limit)
-fn generate_letter_labels(rows: u32, cols: u32) -> Vec<String> {
-    let total_cells = (rows * cols) as usize;
+/// # Returns
+/// - `Ok((length, kept, extended))`: `kept` codes of `length` letters are
+///   labels as they are, and `extended` more are each extended by one letter
+///   (only as many extensions as needed become labels)
+/// - `Err` if the cells would need labels longer than [`MAX_LABEL_LENGTH`]
+fn plan_label_lengths(total_cells: usize, base: usize) -> Result<(u32, usize, usize)> {
+    if total_cells > label_capacity(base) {
+        return Err(anyhow!(
+            "Grid too large: {} cells exceeds maximum of {} for a {}-letter alphabet \
+             (labels of up to {} letters).\n\
+             Reduce grid_rows/grid_cols or use a longer label alphabet.",
+            total_cells,
+            label_capacity(base),
+            base,
+            MAX_LABEL_LENGTH
+        ));
+    }
 
-    // Home row letters (most ergonomic, used first)
-    let home_row = ['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l'];
+    // Find the shortest length whose full set of codes covers every cell
+    let mut length = MIN_LABEL_LENGTH;
+    while base.pow(length) < total_cells {
+        length += 1;
+    }
 
-    // Remaining letters to reach 26 total
-    let extended = ['b', 'c', 'e', 'i', 'm', 'n', 'o', 'p', 'q', 'r', 't', 'u', 'v', 'w', 'x', 'y', 'z'];
+    if length == MIN_LABEL_LENGTH {
+        return Ok((length, total_cells, 0));
+    }
 
-    // Combine home row and extended letters
-    let mut letter_set = Vec::with_capacity(26);
-    letter_set.extend_from_slice(&home_row);
-    letter_set.extend_from_slice(&extended);
+    // Keep most codes one letter shorter and extend only the last few. Each
+    // extended code turns one label into `base` labels, a gain of `base - 1`.
+    let short_length = length - 1;
+    let short_count = base.pow(short_length);
+    let extended = (total_cells - short_count).div_ceil(base - 1);
+    Ok((short_length, short_count - extended, extended))
+}
 
-    assert!(
-        total_cells <= 676,
-        "Grid too large: {} cells exceeds maximum of 676 (26×26)",
-        total_cells
-    );
+/// Choose the block size (in cells) for regional labels
+///
+/// The grid is cut into blocks of `height` × `width` cells, one block per
+/// first letter and one cell per second letter, so both the number of blocks
+/// and the cells per block must fit in the alphabet. Among the possible sizes
+/// the one where both keystrokes narrow the choice about equally is preferred,
+/// then the squarest block.
+///
+/// # Returns
+/// `Some((height, width))`, or `None` if the grid cannot be split this way
+fn regional_block_size(rows: u32, cols: u32, letters: usize) -> Option<(u32, u32)> {
+    let letters = letters as u32;
+    (1..=rows.min(letters))
+        .flat_map(|height| (1..=cols.min(letters / height)).map(move |width| (height, width)))
+        .filter(|&(height, width)| rows.div_ceil(height) * cols.div_ceil(width) <= letters)
+        .min_by_key(|&(height, width)| {
+            let blocks = rows.div_ceil(height) * cols.div_ceil(width);
+            (blocks.abs_diff(height * width), height.abs_diff(width))
+        })
+}
 
-    let mut labels = Vec::with_capacity(total_cells);
+/// The error returned when a grid is too large for regional labels
+fn regional_capacity_error(rows: u32, cols: u32, letters: usize) -> anyhow::Error {
+    anyhow!(
+        "A {}x{} grid cannot be split into at most {} blocks of at most {} cells, \
+         as regional labels require.\n\
+         Use a smaller grid, a longer label alphabet, or another label_mode.",
+        rows,
+        cols,
+        letters,
+        letters
+    )
+}
 
-    // Generate labels in row-major order
-    for i in 0..total_cells {
-        let first_idx = i / 26;
-        let second_idx = i % 26;
-        let label = format!("{}{}", letter_set[first_idx], letter_set[second_idx]);
-        labels.push(label);
+/// Generate regional labels: the first letter names a block of cells, the
+/// second letter a cell within that block
+///
+/// Blocks and the cells inside them are both lettered in row-major order.
+/// Blocks on the right and bottom edges may be smaller than the others.
+///
+/// # Returns
+/// - `Ok(Vec<String>)` with labels in row-major cell order
+/// - `Err` if the grid cannot be split into few enough blocks
+fn generate_regional_labels(rows: u32, cols: u32, alphabet: &Alphabet) -> Result<Vec<String>> {
+    let letters = alphabet.letters();
+    let (height, width) = regional_block_size(rows, cols, letters.len())
+        .ok_or_else(|| regional_capacity_error(rows, cols, letters.len()))?;
+    let blocks_per_row = cols.div_ceil(width);
+
+    let mut labels = Vec::with_capacity((rows * cols) as usize);
+    for row in 0..rows {
+        for col in 0..cols {
+            let block = (row / height) * blocks_per_row + col / width;
+            let block_width = width.min(cols - col / width * width);
+            let within = (row % height) * block_width + col % width;
+            labels.push(format!("{}{}", letters[block as usize], letters[within as usize]));
+        }
     }
 
-    labels
+    Ok(labels)
+}
+
+/// The error returned when a grid is too large for coordinate labels
+fn coordinate_capacity_error(rows: u32, cols: u32, letters: usize) -> anyhow::Error {
+    anyhow!(
+        "A {}x{} grid needs a letter for every row and every column, \
+         but coordinate labels only have {} letters.\n\
+         Use a smaller grid, a longer label alphabet, or another label_mode.",
+        rows,
+        cols,
+        letters
+    )
+}
+
+/// Generate coordinate labels: a row letter followed by a column letter
+///
+/// Rows and columns are both lettered from the start of the alphabet, so
+/// with the default alphabet the top row is `a`, the next `s`, and so on.
+///
+/// # Returns
+/// - `Ok(Vec<String>)` with labels in row-major cell order
+/// - `Err` if there are more rows or columns than letters
+fn generate_coordinate_labels(rows: u32, cols: u32, alphabet: &Alphabet) -> Result<Vec<String>> {
+    let letters = alphabet.letters();
+    if rows.max(cols) as usize > letters.len() {
+        return Err(coordinate_capacity_error(rows, cols, letters.len()));
+    }
+
+    Ok((0..rows as usize)
+        .flat_map(|row| (0..cols as usize).map(move |col| format!("{}{}", letters[row], letters[col])))
+        .collect())
 }
 
 #[cfg(test)]
@@ -208,7 +1012,7 @@ mod tests {
 
     #[test]
     fn test_letter_generation_small_grid() {
-        let labels = generate_letter_labels(2, 2);
+        let labels = generate_letter_labels(2, 2, &Alphabet::default()).unwrap();
         assert_eq!(labels.len(), 4);
         assert_eq!(labels[0], "aa");
         assert_eq!(labels[1], "as");
@@ -218,7 +1022,7 @@ mod tests {
 
     #[test]
     fn test_letter_generation_home_row_priority() {
-        let labels = generate_letter_labels(1, 10);
+        let labels = generate_letter_labels(1, 10, &Alphabet::default()).unwrap();
         // First 9 should use home row letters
         assert_eq!(labels[0], "aa");
         assert_eq!(labels[1], "as");
@@ -228,9 +1032,48 @@ mod tests {
         assert_eq!(labels[9], "ab");
     }
 
+    #[test]
+    fn test_custom_alphabet_labels() {
+        let alphabet = Alphabet::new("arstneio").unwrap();
+        let labels = generate_letter_labels(2, 5, &alphabet).unwrap();
+        assert_eq!(labels, vec!["aa", "ar", "as", "at", "an", "ae", "ai", "ao", "ra", "rr"]);
+    }
+
+    #[test]
+    fn test_alphabet_validation() {
+        assert!(Alphabet::new("asdfghjkl").is_ok());
+        assert_eq!(Alphabet::new("ASDF").unwrap().letters(), ['a', 's', 'd', 'f']);
+
+        let err = Alphabet::new("asdfa").unwrap_err();
+        assert!(err.to_string().contains("Duplicate letter 'a'"));
+
+        let err = Alphabet::new("asd;").unwrap_err();
+        assert!(err.to_string().contains("Invalid character ';' at position 4"));
+
+        assert!(Alphabet::new("a").is_err());
+        assert!(Alphabet::new("").is_err());
+    }
+
+    #[test]
+    fn test_grid_uses_label_options() {
+        let options = LabelOptions {
+            alphabet: Alphabet::new("htns").unwrap(),
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 400, height: 400 };
+        let grid = Grid::for_area(4, 4, area, &options).unwrap();
+
+        assert_eq!(grid.cell_at(0, 0).unwrap().label, "hh");
+        assert_eq!(grid.cell_at(3, 3).unwrap().label, "ss");
+
+        // Sub-grids keep the same alphabet
+        let sub = grid.subdivide("hh", 2, 2).unwrap();
+        assert_eq!(sub.get_all_labels(), vec!["hh", "hn", "hs", "ht"]);
+    }
+
     #[test]
     fn test_grid_creation() {
-        let grid = Grid::new(10, 20, 1920, 1080);
+        let grid = Grid::new(10, 20, 1920, 1080).unwrap();
         assert_eq!(grid.rows, 10);
         assert_eq!(grid.cols, 20);
         assert_eq!(grid.total_cells(), 200);
@@ -239,7 +1082,7 @@ mod tests {
     #[test]
     fn test_cell_coordinates_simple() {
         // Simple case: 10×10 grid on 1000×1000 screen
-        let grid = Grid::new(10, 10, 1000, 1000);
+        let grid = Grid::new(10, 10, 1000, 1000).unwrap();
 
         // Cell at (0,0) should be at top-left
         let cell = grid.get_cell("aa").unwrap();
@@ -266,7 +1109,7 @@ mod tests {
     #[test]
     fn test_realistic_monitor() {
         // Test with real monitor dimensions: 1920×1080, 10×20 grid
-        let grid = Grid::new(10, 20, 1920, 1080);
+        let grid = Grid::new(10, 20, 1920, 1080).unwrap();
 
         let cell = grid.get_cell("aa").unwrap();
         assert_eq!(cell.coordinates.width, 96);  // 1920 / 20
@@ -279,12 +1122,16 @@ mod tests {
 
     #[test]
     fn test_cell_lookup() {
-        let grid = Grid::new(5, 5, 1000, 1000);
+        let grid = Grid::new(5, 5, 1000, 1000).unwrap();
 
         assert!(grid.has_label("aa"));
         assert!(grid.has_label("as"));
         assert!(!grid.has_label("zz"));
 
+        assert!(grid.has_prefix("a"));
+        assert!(grid.has_prefix("as"));
+        assert!(!grid.has_prefix("z"));
+
         let cell = grid.get_cell("aa");
         assert!(cell.is_some());
 
@@ -295,7 +1142,7 @@ mod tests {
     #[test]
     fn test_row_major_ordering() {
         // 2×3 grid should have cells in order: aa, as, ad (row 0), af, ag, ah (row 1)
-        let grid = Grid::new(2, 3, 600, 400);
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
 
         let cell_aa = grid.get_cell("aa").unwrap();
         assert_eq!(cell_aa.grid_position, (0, 0));
@@ -310,22 +1157,261 @@ mod tests {
         assert_eq!(cell_af.grid_position, (1, 0));
     }
 
+    /// Assert that the cells of `grid` tile its area exactly: every cell lies
+    /// inside the area, no two cells overlap, and together they cover it
+    fn assert_exact_tiling(grid: &Grid) {
+        let area = &grid.area;
+        let cells: Vec<&CellCoordinates> = grid
+            .get_all_labels()
+            .iter()
+            .map(|label| &grid.get_cell(label).unwrap().coordinates)
+            .collect();
+
+        for c in &cells {
+            assert!(c.x >= area.x && c.y >= area.y, "{:?} starts outside {:?}", c, area);
+            assert!(c.x + c.width as i32 <= area.x + area.width as i32, "{:?} overflows {:?}", c, area);
+            assert!(c.y + c.height as i32 <= area.y + area.height as i32, "{:?} overflows {:?}", c, area);
+        }
+
+        for (i, a) in cells.iter().enumerate() {
+            for b in &cells[i + 1..] {
+                let overlap_x = a.x < b.x + b.width as i32 && b.x < a.x + a.width as i32;
+                let overlap_y = a.y < b.y + b.height as i32 && b.y < a.y + a.height as i32;
+                assert!(!(overlap_x && overlap_y), "{:?} overlaps {:?}", a, b);
+            }
+        }
+
+        // Inside the area and non-overlapping, so equal total area means full coverage
+        let covered: u64 = cells.iter().map(|c| c.width as u64 * c.height as u64).sum();
+        assert_eq!(covered, area.width as u64 * area.height as u64);
+    }
+
     #[test]
     fn test_all_cells_within_bounds() {
-        let grid = Grid::new(10, 20, 1920, 1080);
+        let cases = [
+            (10, 20, 1920, 1080),
+            (10, 7, 1920, 1080),
+            (13, 17, 2560, 1440),
+            (20, 10, 1080, 1920),
+            (26, 26, 3440, 1440),
+            (9, 11, 1366, 768),
+            (5, 5, 7, 13),
+        ];
+
+        for (rows, cols, width, height) in cases {
+            assert_exact_tiling(&Grid::new(rows, cols, width, height).unwrap());
+
+            let offset = CellCoordinates { x: -(width as i32), y: 37, width, height };
+            assert_exact_tiling(&Grid::for_area(rows, cols, offset, &LabelOptions::default()).unwrap());
+        }
+    }
 
+    #[test]
+    fn test_remainder_spread_across_cells() {
+        // 1920 / 7 = 274.28..., so cells are 274 or 275 px wide
+        let grid = Grid::new(1, 7, 1920, 1080).unwrap();
+        let mut widths: Vec<u32> = (0..7)
+            .map(|col| grid.cell_at(0, col).unwrap().coordinates.width)
+            .collect();
+        assert_eq!(widths.iter().sum::<u32>(), 1920);
+        widths.sort();
+        assert_eq!(widths[0], 274);
+        assert_eq!(widths[6], 275);
+
+        // The right-most cell reaches the screen edge
+        let last = &grid.cell_at(0, 6).unwrap().coordinates;
+        assert_eq!(last.x + last.width as i32, 1920);
+    }
+
+    #[test]
+    fn test_reserved_strips_tile_exactly() {
+        let area = CellCoordinates { x: 0, y: 0, width: 1920, height: 1080 };
+        let options = LabelOptions::default();
+        assert_exact_tiling(&Grid::with_reserved_strips(10, 7, area, [0, 38, 0, 0], &options).unwrap());
+
+        let area = CellCoordinates { x: 100, y: -50, width: 1000, height: 999 };
+        assert_exact_tiling(&Grid::with_reserved_strips(6, 9, area, [31, 17, 22, 41], &options).unwrap());
+    }
+
+    #[test]
+    fn test_offset_monitor_global_coordinates() {
+        // Monitor placed right of a 1920px primary display
+        let area = CellCoordinates { x: 1920, y: 0, width: 1920, height: 1080 };
+        let grid = Grid::for_area(10, 20, area, &LabelOptions::default()).unwrap();
+
+        let cell = grid.get_cell("aa").unwrap();
+        assert_eq!((cell.coordinates.x, cell.coordinates.y), (1920, 0));
+        assert_eq!(cell.coordinates.center(), (1968, 54));
+    }
+
+    #[test]
+    fn test_negative_offset_monitor() {
+        // Monitor placed left of and above the origin
+        let area = CellCoordinates { x: -1920, y: -1080, width: 1920, height: 1080 };
+        let grid = Grid::for_area(10, 20, area, &LabelOptions::default()).unwrap();
+
+        let first = grid.get_cell("aa").unwrap();
+        assert_eq!((first.coordinates.x, first.coordinates.y), (-1920, -1080));
+        assert_eq!(first.coordinates.center(), (-1872, -1026));
+
+        // Every cell stays inside the monitor's global rectangle
         for label in grid.get_all_labels() {
-            let cell = grid.get_cell(&label).unwrap();
-            assert!(cell.coordinates.x < grid.monitor_width);
-            assert!(cell.coordinates.y < grid.monitor_height);
-            assert!(cell.coordinates.x + cell.coordinates.width <= grid.monitor_width);
-            assert!(cell.coordinates.y + cell.coordinates.height <= grid.monitor_height);
+            let c = &grid.get_cell(&label).unwrap().coordinates;
+            assert!(c.x >= -1920 && c.x + c.width as i32 <= 0);
+            assert!(c.y >= -1080 && c.y + c.height as i32 <= 0);
+        }
+    }
+
+    #[test]
+    fn test_inset_area() {
+        let area = CellCoordinates { x: -1920, y: 0, width: 1920, height: 1080 };
+        let usable = area.inset([0, 38, 0, 0]);
+        assert_eq!(usable, CellCoordinates { x: -1920, y: 38, width: 1920, height: 1042 });
+
+        // Oversized insets clamp to an empty rectangle
+        let empty = area.inset([2000, 0, 0, 2000]);
+        assert_eq!((empty.width, empty.height), (0, 0));
+    }
+
+    #[test]
+    fn test_grid_over_usable_area() {
+        let area = CellCoordinates { x: 0, y: 0, width: 1920, height: 1080 };
+        let grid = Grid::for_area(10, 20, area.inset([0, 38, 0, 0]), &LabelOptions::default()).unwrap();
+
+        let first = grid.get_cell("aa").unwrap();
+        assert_eq!(first.coordinates.y, 38);
+        for label in grid.get_all_labels() {
+            assert!(grid.get_cell(&label).unwrap().coordinates.y >= 38);
+        }
+    }
+
+    #[test]
+    fn test_reserved_strip_gets_dedicated_row() {
+        let area = CellCoordinates { x: 0, y: 0, width: 1920, height: 1080 };
+        let grid = Grid::with_reserved_strips(10, 20, area, [0, 38, 0, 0], &LabelOptions::default()).unwrap();
+
+        // One extra row for the top bar
+        assert_eq!(grid.rows, 11);
+        assert_eq!(grid.cols, 20);
+        assert_eq!(grid.total_cells(), 220);
+
+        // The first row spans exactly the bar
+        let bar = grid.get_cell("aa").unwrap();
+        assert_eq!(bar.grid_position, (0, 0));
+        assert_eq!((bar.coordinates.y, bar.coordinates.height), (0, 38));
+
+        // The next row starts right below it
+        let below = grid.cell_at(1, 0).unwrap();
+        assert_eq!(below.coordinates.y, 38);
+    }
+
+    #[test]
+    fn test_reserved_strips_on_every_edge() {
+        let area = CellCoordinates { x: 100, y: 0, width: 1000, height: 1000 };
+        let grid = Grid::with_reserved_strips(4, 4, area, [50, 30, 20, 40], &LabelOptions::default()).unwrap();
+
+        assert_eq!(grid.rows, 6);
+        assert_eq!(grid.cols, 6);
+
+        let corner = grid.get_cell("aa").unwrap();
+        assert_eq!(corner.coordinates, CellCoordinates { x: 100, y: 0, width: 50, height: 30 });
+
+        let last = grid.cell_at(5, 5).unwrap();
+        assert_eq!(last.coordinates, CellCoordinates { x: 1080, y: 960, width: 20, height: 40 });
+    }
+
+    #[test]
+    fn test_no_reserved_strips_matches_plain_grid() {
+        let area = CellCoordinates { x: 0, y: 0, width: 1920, height: 1080 };
+        let plain = Grid::for_area(10, 20, area.clone(), &LabelOptions::default()).unwrap();
+        let strips = Grid::with_reserved_strips(10, 20, area, [0, 0, 0, 0], &LabelOptions::default()).unwrap();
+
+        assert_eq!(strips.rows, plain.rows);
+        assert_eq!(strips.cols, plain.cols);
+        for label in plain.get_all_labels() {
+            assert_eq!(
+                plain.get_cell(&label).unwrap().coordinates,
+                strips.get_cell(&label).unwrap().coordinates
+            );
+        }
+    }
+
+    #[test]
+    fn test_subdivide_cell() {
+        let grid = Grid::new(10, 20, 1920, 1080).unwrap();
+        let parent = grid.get_cell("as").unwrap().coordinates.clone();
+
+        let sub = grid.subdivide("as", 3, 3).unwrap();
+        assert_eq!(sub.area, parent);
+        assert_eq!(sub.total_cells(), 9);
+
+        // Sub-grid labels are generated the same way as the main grid
+        let first = sub.get_cell("aa").unwrap();
+        assert_eq!((first.coordinates.x, first.coordinates.y), (parent.x, parent.y));
+        assert_exact_tiling(&sub);
+    }
+
+    #[test]
+    fn test_subdivide_unknown_label() {
+        let grid = Grid::new(2, 2, 100, 100).unwrap();
+        let err = grid.subdivide("zz", 3, 3).unwrap_err();
+        assert!(err.to_string().contains("'zz'"));
+    }
+
+    #[test]
+    fn test_subdivide_clamps_to_pixels() {
+        let area = CellCoordinates { x: 10, y: 10, width: 2, height: 1 };
+        let grid = Grid::for_area(1, 1, area, &LabelOptions::default()).unwrap();
+
+        // Only two one-pixel columns fit
+        let sub = grid.subdivide("aa", 5, 5).unwrap();
+        assert_eq!((sub.rows, sub.cols), (1, 2));
+        assert_exact_tiling(&sub);
+
+        // A single pixel cannot be refined further
+        let err = sub.subdivide("aa", 5, 5).unwrap_err();
+        assert!(err.to_string().contains("single pixel"));
+    }
+
+    #[test]
+    fn test_recursive_refinement_reaches_any_pixel() {
+        // Every probed pixel on a 4K screen is reachable within a few levels
+        let targets = [(0, 0), (3839, 2159), (1234, 567), (1920, 1080), (2047, 17)];
+
+        for (tx, ty) in targets {
+            let mut grid = Grid::new(10, 20, 3840, 2160).unwrap();
+            let mut depth = 0;
+
+            loop {
+                let label = grid
+                    .get_all_labels()
+                    .into_iter()
+                    .find(|label| {
+                        let c = &grid.get_cell(label).unwrap().coordinates;
+                        tx >= c.x && tx < c.x + c.width as i32 && ty >= c.y && ty < c.y + c.height as i32
+                    })
+                    .unwrap();
+
+                match grid.subdivide(&label, 5, 5) {
+                    Ok(sub) => {
+                        grid = sub;
+                        depth += 1;
+                    }
+                    Err(_) => {
+                        let c = &grid.get_cell(&label).unwrap().coordinates;
+                        assert_eq!((c.x, c.y, c.width, c.height), (tx, ty, 1, 1));
+                        break;
+                    }
+                }
+            }
+
+            assert!(depth <= 4, "pixel ({}, {}) needed {} refinements", tx, ty, depth);
         }
     }
 
     #[test]
     fn test_unique_labels() {
-        let grid = Grid::new(15, 15, 1920, 1080);
+        let grid = Grid::new(15, 15, 1920, 1080).unwrap();
         let labels = grid.get_all_labels();
         let unique_count = labels.len();
         let total_count = grid.total_cells();
@@ -334,24 +1420,90 @@ mod tests {
         assert_eq!(total_count, 225);
     }
 
+    /// Assert that no label is a prefix of another (or a duplicate)
+    fn assert_prefix_free(labels: &[String]) {
+        let mut sorted = labels.to_vec();
+        sorted.sort();
+        for pair in sorted.windows(2) {
+            assert!(
+                !pair[1].starts_with(&pair[0]),
+                "'{}' is a prefix of '{}'",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
     #[test]
-    #[should_panic(expected = "Grid too large")]
-    fn test_grid_too_large() {
-        // 27×27 = 729 cells, exceeds 676 limit
-        generate_letter_labels(27, 27);
+    fn test_labels_extend_beyond_676_cells() {
+        // 27×27 = 729 cells, more than 676 two-letter labels: some get three
+        let labels = generate_letter_labels(27, 27, &Alphabet::default()).unwrap();
+        assert_eq!(labels.len(), 729);
+        assert_prefix_free(&labels);
+
+        // Only as many labels as needed are extended: 673 keep two letters
+        assert_eq!(labels.iter().filter(|l| l.len() == 2).count(), 673);
+        assert_eq!(labels.iter().filter(|l| l.len() == 3).count(), 56);
+        assert!(labels.iter().all(|l| l.len() <= 3));
+
+        // The earliest cells keep the shortest labels
+        assert_eq!(labels[0], "aa");
+        assert_eq!(labels[672].len(), 2);
+        assert_eq!(labels[673].len(), 3);
     }
 
     #[test]
     fn test_edge_case_maximum_grid() {
-        // 26×26 = 676 cells, exactly at limit
-        let labels = generate_letter_labels(26, 26);
+        // 26×26 = 676 cells, the most that fit in two letters
+        let labels = generate_letter_labels(26, 26, &Alphabet::default()).unwrap();
         assert_eq!(labels.len(), 676);
+        assert!(labels.iter().all(|l| l.len() == 2));
+        assert_prefix_free(&labels);
+    }
+
+    #[test]
+    fn test_largest_config_grid_labels() {
+        // 50×50 is the largest grid allowed by the configuration
+        let labels = generate_letter_labels(50, 50, &Alphabet::default()).unwrap();
+        assert_eq!(labels.len(), 2500);
+        assert_prefix_free(&labels);
+        assert!(labels.iter().all(|l| l.len() == 2 || l.len() == 3));
+    }
+
+    #[test]
+    fn test_small_alphabet_needs_longer_labels() {
+        // 3 letters: 9 pairs, 27 triples, 81 quadruples
+        let alphabet = Alphabet::new("asd").unwrap();
+
+        let labels = generate_letter_labels(2, 5, &alphabet).unwrap();
+        assert_eq!(labels.len(), 10);
+        assert_prefix_free(&labels);
+        assert_eq!(labels.iter().filter(|l| l.len() == 3).count(), 2);
+
+        let labels = generate_letter_labels(9, 9, &alphabet).unwrap();
+        assert_eq!(labels.len(), 81);
+        assert!(labels.iter().all(|l| l.len() == 4));
+    }
+
+    #[test]
+    fn test_too_many_cells_is_an_error() {
+        // 3 letters cannot label more than 3^4 = 81 cells
+        let alphabet = Alphabet::new("asd").unwrap();
+        let err = generate_letter_labels(9, 10, &alphabet).unwrap_err();
+        assert!(err.to_string().contains("Grid too large"));
+
+        let area = CellCoordinates { x: 0, y: 0, width: 100, height: 100 };
+        let options = LabelOptions {
+            alphabet,
+            ..LabelOptions::default()
+        };
+        assert!(Grid::for_area(9, 10, area, &options).is_err());
     }
 
     #[test]
     fn test_non_square_grid() {
         // Test with non-square grid
-        let grid = Grid::new(5, 20, 1920, 1080);
+        let grid = Grid::new(5, 20, 1920, 1080).unwrap();
         assert_eq!(grid.rows, 5);
         assert_eq!(grid.cols, 20);
         assert_eq!(grid.total_cells(), 100);
@@ -360,4 +1512,245 @@ mod tests {
         assert_eq!(cell.coordinates.width, 96);  // 1920 / 20
         assert_eq!(cell.coordinates.height, 216); // 1080 / 5
     }
+
+    #[test]
+    fn test_sequence_effort_model() {
+        use crate::layout::{DVORAK, QWERTY};
+
+        // Home row hand alternation beats everything else
+        assert!(sequence_effort(&QWERTY, "fj") < sequence_effort(&QWERTY, "fd"));
+        // Same hand beats same finger
+        assert!(sequence_effort(&QWERTY, "fd") < sequence_effort(&QWERTY, "fr"));
+        // Row jumps cost extra: home to top is cheaper than top to bottom
+        assert!(sequence_effort(&QWERTY, "de") < sequence_effort(&QWERTY, "ec"));
+        assert!(sequence_effort(&QWERTY, "sd") < sequence_effort(&QWERTY, "wc"));
+        // Pinkies and index stretches are harder than strong fingers at home
+        assert!(sequence_effort(&QWERTY, "dk") < sequence_effort(&QWERTY, "ap"));
+        assert!(sequence_effort(&QWERTY, "dk") < sequence_effort(&QWERTY, "gh"));
+        // Repeating a key is cheaper than moving the same finger
+        assert!(sequence_effort(&QWERTY, "ff") < sequence_effort(&QWERTY, "fg"));
+        // The model follows the physical layout
+        assert_eq!(sequence_effort(&QWERTY, "fj"), sequence_effort(&DVORAK, "uh"));
+        assert!(sequence_effort(&DVORAK, "fj") > sequence_effort(&DVORAK, "uh"));
+    }
+
+    #[test]
+    fn test_ergonomic_labels_favor_center() {
+        let options = LabelOptions {
+            mode: LabelMode::Ergonomic,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 1900, height: 1100 };
+        let grid = Grid::for_area(11, 19, area.clone(), &options).unwrap();
+        assert_exact_tiling(&grid);
+
+        // The center cell gets the easiest label of the whole set
+        let effort = |label: &str| sequence_effort(options.layout, label);
+        let center = &grid.cell_at(5, 9).unwrap().label;
+        let easiest = grid.get_all_labels().into_iter().map(|l| effort(&l)).min().unwrap();
+        assert_eq!(effort(center), easiest);
+
+        // Corners get harder labels than the center
+        for (row, col) in [(0, 0), (0, 18), (10, 0), (10, 18)] {
+            assert!(effort(&grid.cell_at(row, col).unwrap().label) > effort(center));
+        }
+    }
+
+    #[test]
+    fn test_ergonomic_labels_are_easier_than_sequential() {
+        use crate::layout::QWERTY;
+
+        let effort = |labels: &[String]| -> u32 { labels.iter().map(|l| sequence_effort(&QWERTY, l)).sum() };
+
+        // Two-letter labels only, a mix of two and three letters, and a few
+        for (rows, cols) in [(10, 20), (27, 27), (5, 5)] {
+            let sequential = generate_letter_labels(rows, cols, &Alphabet::default()).unwrap();
+            let ergonomic =
+                generate_ergonomic_labels(rows, cols, &Alphabet::default(), &QWERTY).unwrap();
+
+            assert_eq!(ergonomic.len(), sequential.len());
+            assert_prefix_free(&ergonomic);
+            assert!(effort(&ergonomic) < effort(&sequential), "{}x{}", rows, cols);
+        }
+
+        // Awkward same-finger pairs are left out when easier ones are free
+        let labels = generate_ergonomic_labels(10, 20, &Alphabet::default(), &QWERTY).unwrap();
+        assert!(!labels.contains(&"az".to_string()));
+        assert!(!labels.contains(&"aq".to_string()));
+    }
+
+    #[test]
+    fn test_ergonomic_labels_stay_short_near_center() {
+        // 27×27 needs some three-letter labels; they go to the edges
+        let options = LabelOptions {
+            mode: LabelMode::Ergonomic,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 2700, height: 2700 };
+        let grid = Grid::for_area(27, 27, area, &options).unwrap();
+
+        assert_eq!(grid.cell_at(13, 13).unwrap().label.len(), 2);
+        assert_eq!(grid.cell_at(0, 0).unwrap().label.len(), 3);
+    }
+
+    #[test]
+    fn test_regional_block_size() {
+        // 200 cells: 15 blocks of 4×4, both keys narrow the choice ~15-fold
+        assert_eq!(regional_block_size(10, 20, 26), Some((4, 4)));
+        for (rows, cols) in [(2, 2), (5, 5), (10, 20), (20, 10), (26, 26), (9, 3)] {
+            let (height, width) = regional_block_size(rows, cols, 26).unwrap();
+            assert!(height * width <= 26);
+            assert!(rows.div_ceil(height) * cols.div_ceil(width) <= 26);
+        }
+        // 675 cells fit in 26² pairs, but not in 26 blocks of 26
+        assert_eq!(regional_block_size(27, 25, 26), None);
+    }
+
+    #[test]
+    fn test_regional_labels() {
+        let options = LabelOptions {
+            mode: LabelMode::Regional,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 2000, height: 1000 };
+        let grid = Grid::for_area(10, 20, area, &options).unwrap();
+        assert_eq!(grid.total_cells(), 200);
+        assert_exact_tiling(&grid);
+
+        // The first block covers the top-left 4×4 cells
+        assert_eq!(grid.cell_at(0, 0).unwrap().label, "aa");
+        assert_eq!(grid.cell_at(0, 3).unwrap().label, "af");
+        assert_eq!(grid.cell_at(3, 3).unwrap().label, "ao");
+        assert_eq!(grid.cell_at(0, 4).unwrap().label, "sa");
+
+        // Every cell sharing a first letter lies in that letter's block
+        let block = grid.prefix_bounds("a").unwrap();
+        assert_eq!(block, CellCoordinates { x: 0, y: 0, width: 400, height: 400 });
+
+        // The bottom blocks are only two rows high
+        let bottom = grid.prefix_bounds(&grid.cell_at(9, 19).unwrap().label[..1]).unwrap();
+        assert_eq!(bottom, CellCoordinates { x: 1600, y: 800, width: 400, height: 200 });
+    }
+
+    #[test]
+    fn test_regional_labels_too_large() {
+        let options = LabelOptions {
+            mode: LabelMode::Regional,
+            ..LabelOptions::default()
+        };
+        assert!(options.check_capacity(26, 26).is_ok());
+        assert!(options.check_capacity(27, 25).is_err());
+
+        let area = CellCoordinates { x: 0, y: 0, width: 2700, height: 2500 };
+        let err = Grid::for_area(27, 25, area, &options).unwrap_err();
+        assert!(err.to_string().contains("regional labels"));
+    }
+
+    #[test]
+    fn test_prefix_bounds() {
+        let grid = Grid::new(10, 20, 1920, 1080).unwrap();
+
+        // Sequential "a*" labels fill row 0 and wrap into row 1
+        let bounds = grid.prefix_bounds("a").unwrap();
+        assert_eq!((bounds.x, bounds.y), (0, 0));
+        assert_eq!((bounds.width, bounds.height), (1920, 216));
+
+        // A full label bounds its own cell
+        assert_eq!(grid.prefix_bounds("aa").unwrap(), grid.get_cell("aa").unwrap().coordinates);
+        assert_eq!(grid.prefix_bounds(""), Some(grid.area.clone()));
+        assert_eq!(grid.prefix_bounds("zz"), None);
+    }
+
+    #[test]
+    fn test_cells_with_prefix() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+
+        let labels: Vec<&str> = grid.cells_with_prefix("a").map(|cell| cell.label.as_str()).collect();
+        assert_eq!(labels, vec!["aa", "as", "ad", "af", "ag", "ah"]);
+
+        let matches: Vec<&GridCell> = grid.cells_with_prefix("ad").collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].grid_position, (0, 2));
+
+        assert_eq!(grid.cells_with_prefix("").count(), 6);
+        assert_eq!(grid.cells_with_prefix("s").count(), 0);
+        assert!(!grid.has_prefix("s"));
+    }
+
+    #[test]
+    fn test_coordinate_labels() {
+        let options = LabelOptions {
+            mode: LabelMode::Coordinates,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 2000, height: 1000 };
+        let grid = Grid::for_area(10, 20, area, &options).unwrap();
+        assert_exact_tiling(&grid);
+
+        // Row letter first, column letter second
+        let cell = grid.get_cell("sd").unwrap();
+        assert_eq!(cell.grid_position, (1, 2));
+        assert_eq!((cell.coordinates.x, cell.coordinates.y), (200, 100));
+        assert_eq!(grid.cell_at(9, 19).unwrap().label, "bt");
+
+        // Letters beyond the last row or column resolve to nothing
+        assert!(grid.get_cell("ca").is_none());
+        assert!(grid.get_cell("ay").is_none());
+        assert!(grid.get_cell("a").is_none());
+        assert!(grid.get_cell("asd").is_none());
+        assert!(grid.has_prefix("b"));
+        assert!(!grid.has_prefix("c"));
+
+        // A row letter narrows the choice to that whole row
+        let row = grid.prefix_bounds("s").unwrap();
+        assert_eq!(row, CellCoordinates { x: 0, y: 100, width: 2000, height: 100 });
+    }
+
+    #[test]
+    fn test_coordinate_rulers() {
+        let options = LabelOptions {
+            mode: LabelMode::Coordinates,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: -1920, y: 0, width: 1920, height: 1080 };
+        let grid = Grid::for_area(3, 4, area, &options).unwrap();
+
+        let (rows, columns) = grid.rulers().unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Ruler { letter: 'a', start: 0, length: 360 },
+                Ruler { letter: 's', start: 360, length: 360 },
+                Ruler { letter: 'd', start: 720, length: 360 },
+            ]
+        );
+        assert_eq!(columns.len(), 4);
+        assert_eq!(columns[3], Ruler { letter: 'f', start: -480, length: 480 });
+
+        // Other modes label every cell instead
+        assert!(Grid::new(3, 4, 1920, 1080).unwrap().rulers().is_none());
+    }
+
+    #[test]
+    fn test_coordinate_labels_too_large() {
+        let options = LabelOptions {
+            mode: LabelMode::Coordinates,
+            alphabet: Alphabet::new("asdfghjkl").unwrap(),
+            ..LabelOptions::default()
+        };
+        assert!(options.check_capacity(9, 9).is_ok());
+        assert!(options.check_capacity(10, 2).is_err());
+
+        let area = CellCoordinates { x: 0, y: 0, width: 1000, height: 1000 };
+        let err = Grid::for_area(2, 10, area, &options).unwrap_err();
+        assert!(err.to_string().contains("coordinate labels"));
+    }
+
+    #[test]
+    fn test_cell_at_out_of_bounds() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        assert_eq!(grid.cell_at(1, 2).unwrap().grid_position, (1, 2));
+        assert!(grid.cell_at(2, 0).is_none());
+        assert!(grid.cell_at(0, 3).is_none());
+    }
 }
@@ -0,0 +1,174 @@
+// Hyprland IPC module for HyprGrid
+//
+// This module queries Hyprland directly over its request socket instead of
+// spawning `hyprctl`, which saves a process fork every time the grid is
+// summoned. `hyprctl` is still used as a fallback when the socket is missing.
+
+use anyhow::{anyhow, Context, Result};
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+/// How long to wait for Hyprland to answer a request
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Client for Hyprland's request socket (`.socket.sock`)
+#[derive(Debug, Clone)]
+pub struct HyprlandSocket {
+    /// Full path to the Unix socket
+    path: PathBuf,
+}
+
+impl HyprlandSocket {
+    /// Create a client for a socket at an explicit path
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        HyprlandSocket { path: path.into() }
+    }
+
+    /// Locate the socket of the running Hyprland instance
+    ///
+    /// The socket lives at
+    /// `$XDG_RUNTIME_DIR/hypr/$HYPRLAND_INSTANCE_SIGNATURE/.socket.sock`.
+    ///
+    /// # Returns
+    /// `None` if either environment variable is unset
+    pub fn from_env() -> Option<Self> {
+        let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+        let signature = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE")?;
+
+        Some(Self::at(
+            PathBuf::from(runtime_dir)
+                .join("hypr")
+                .join(signature)
+                .join(".socket.sock"),
+        ))
+    }
+
+    /// Check whether the socket file exists
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Send a raw request and return the full response
+    ///
+    /// # Arguments
+    /// * `request` - The request string, e.g. `j/monitors` for JSON monitor info
+    ///
+    /// # Returns
+    /// - `Ok(String)` with the response body
+    /// - `Err` if the socket cannot be reached or the exchange fails
+    pub fn request(&self, request: &str) -> Result<String> {
+        let mut stream = UnixStream::connect(&self.path).with_context(|| {
+            format!(
+                "Failed to connect to Hyprland socket at: {}\n\
+                 Please ensure you are running Hyprland.",
+                self.path.display()
+            )
+        })?;
+
+        stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+        stream.set_write_timeout(Some(SOCKET_TIMEOUT))?;
+
+        stream
+            .write_all(request.as_bytes())
+            .with_context(|| format!("Failed to send '{}' to Hyprland socket", request))?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .with_context(|| format!("Failed to read Hyprland response to '{}'", request))?;
+
+        Ok(response)
+    }
+}
+
+/// Get the JSON description of all monitors from Hyprland
+///
+/// Talks to the Hyprland socket directly when it is available and falls back
+/// to running `hyprctl monitors -j` when it is not.
+pub fn monitors_json() -> Result<String> {
+    match HyprlandSocket::from_env() {
+        Some(socket) if socket.exists() => socket.request("j/monitors"),
+        _ => hyprctl_monitors_json(),
+    }
+}
+
+/// Run `hyprctl monitors -j` and return its output
+fn hyprctl_monitors_json() -> Result<String> {
+    let output = Command::new("hyprctl")
+        .args(["monitors", "-j"])
+        .output()
+        .context(
+            "Failed to execute 'hyprctl monitors -j'.\n\
+             Please ensure you are running Hyprland and hyprctl is installed."
+        )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "hyprctl command failed with status: {}\n\
+             stderr: {}",
+            output.status,
+            stderr
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    const FIXTURE: &str = include_str!("../hyprctl_return_monitors.json");
+
+    /// Serve a single request on a temporary socket, answering with `response`
+    ///
+    /// Returns the socket path and a handle yielding the request received.
+    fn serve_once(name: &str, response: &'static str) -> (PathBuf, thread::JoinHandle<String>) {
+        let path = std::env::temp_dir().join(format!(
+            "hyprgrid-test-{}-{}.sock",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 256];
+            let n = stream.read(&mut buf).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        (path, handle)
+    }
+
+    #[test]
+    fn test_request_monitors_from_socket() {
+        let (path, server) = serve_once("monitors", FIXTURE);
+
+        let socket = HyprlandSocket::at(&path);
+        assert!(socket.exists());
+        let response = socket.request("j/monitors").unwrap();
+
+        assert_eq!(server.join().unwrap(), "j/monitors");
+        assert_eq!(response, FIXTURE);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_socket() {
+        let socket = HyprlandSocket::at("/nonexistent/hypr/.socket.sock");
+        assert!(!socket.exists());
+
+        let err = socket.request("j/monitors").unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to connect to Hyprland socket"));
+    }
+}
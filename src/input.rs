@@ -0,0 +1,678 @@
+// Input handling module for HyprGrid
+//
+// This module implements the keyboard state machine that turns a stream of key
+// events into a cell selection. It is independent of the GUI so the whole
+// interaction can be exercised in unit tests without opening a window.
+
+use serde::{Deserialize, Deserializer};
+
+use crate::grid::{Grid, GridCell};
+
+/// A keyboard event as seen by the input state machine
+///
+/// The GUI layer translates its native key events into this type before
+/// handing them to [`InputHandler::handle_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable character (letters are matched case-insensitively)
+    Char(char),
+    /// The space bar
+    Space,
+    /// The Enter/Return key
+    Enter,
+    /// The Backspace key
+    Backspace,
+    /// The Escape key
+    Escape,
+    /// The Tab key
+    Tab,
+    /// Any other key, which is always ignored
+    Other,
+}
+
+impl Key {
+    /// Look up a key by its name in the `[keys]` table of the configuration
+    ///
+    /// Names are "space", "enter" (or "return"), or a single character such
+    /// as "2" or ".". Letters are returned as well; whether they may be bound
+    /// is up to the caller.
+    pub fn from_name(name: &str) -> Option<Key> {
+        match name.to_ascii_lowercase().as_str() {
+            "space" => Some(Key::Space),
+            "enter" | "return" => Some(Key::Enter),
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if !c.is_whitespace() && !c.is_control() => Some(Key::Char(c)),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// The mouse action requested for the selected cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Left click, triggered by SPACE
+    LeftClick,
+    /// Right click, triggered by ENTER
+    RightClick,
+    /// Middle click (e.g., paste or open a link in a new tab)
+    MiddleClick,
+    /// Two left clicks (e.g., select a word)
+    DoubleClick,
+    /// Three left clicks (e.g., select a line)
+    TripleClick,
+    /// Left click while holding Shift (e.g., extend a selection)
+    ShiftClick,
+    /// Left click while holding Ctrl (e.g., add to a selection)
+    CtrlClick,
+    /// Left click while holding Alt
+    AltClick,
+    /// Move the pointer without clicking (e.g., `hyprgrid move`)
+    Move,
+    /// Press the left button here and release it at a second label
+    Drag,
+}
+
+impl Action {
+    /// Every action with the name used on the command line and in `[keys]`
+    const NAMES: [(&'static str, Action); 10] = [
+        ("left-click", Action::LeftClick),
+        ("right-click", Action::RightClick),
+        ("middle-click", Action::MiddleClick),
+        ("double-click", Action::DoubleClick),
+        ("triple-click", Action::TripleClick),
+        ("shift-click", Action::ShiftClick),
+        ("ctrl-click", Action::CtrlClick),
+        ("alt-click", Action::AltClick),
+        ("move", Action::Move),
+        ("drag", Action::Drag),
+    ];
+
+    /// Look up an action by its name (e.g., "right-click")
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, action)| action)
+    }
+
+    /// Get the accepted action names, comma-separated
+    pub fn names() -> String {
+        Self::NAMES.map(|(name, _)| name).join(", ")
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Action::from_name(&name).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "unknown action '{}', expected one of: {}",
+                name,
+                Action::names()
+            ))
+        })
+    }
+}
+
+/// Which keys confirm a label, and with what action
+///
+/// Letters always type labels and BACKSPACE, ESC and TAB keep their meaning,
+/// so bindings are for SPACE, ENTER and non-letter characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for KeyBindings {
+    /// SPACE and ENTER for left and right click, and a few more on symbols
+    /// and digits that are never part of a label
+    fn default() -> Self {
+        KeyBindings {
+            bindings: vec![
+                (Key::Space, Action::LeftClick),
+                (Key::Enter, Action::RightClick),
+                (Key::Char(','), Action::MiddleClick),
+                (Key::Char('2'), Action::DoubleClick),
+                (Key::Char('3'), Action::TripleClick),
+                (Key::Char('.'), Action::Move),
+                (Key::Char('/'), Action::Drag),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Get the action bound to a key, if any
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|&(_, action)| action)
+    }
+
+    /// Bind a key to an action, replacing its previous binding
+    pub fn bind(&mut self, key: Key, action: Action) {
+        match self.bindings.iter_mut().find(|(bound, _)| *bound == key) {
+            Some(binding) => binding.1 = action,
+            None => self.bindings.push((key, action)),
+        }
+    }
+}
+
+/// A completed selection: which cell was chosen and what to do with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// The label of the selected cell (e.g., "aj")
+    pub label: String,
+    /// The action to perform at the cell center
+    pub action: Action,
+}
+
+/// The current stage of the input state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputState {
+    /// Nothing typed yet
+    FirstChar,
+    /// A valid label prefix has been typed, but not a complete label
+    NextChar,
+    /// A complete label has been typed; waiting for SPACE or ENTER
+    ActionKey,
+}
+
+/// The result of feeding a single key to the state machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputOutcome {
+    /// The key was accepted and the state changed
+    Updated,
+    /// The key was not valid in the current state and was ignored
+    Ignored,
+    /// The key was a letter that does not extend the input to a valid label
+    Rejected,
+    /// The user pressed Escape; the interaction should end without an action
+    Cancelled,
+    /// The user asked to refine the typed cell into a finer sub-grid
+    Refine(String),
+    /// The user completed a selection
+    Selected(Selection),
+}
+
+/// Keyboard state machine for cell selection
+///
+/// The handler accumulates typed characters and validates every prefix
+/// against the grid, so the buffer always holds either nothing, a prefix of at
+/// least one label, or a complete label.
+#[derive(Debug, Default)]
+pub struct InputHandler {
+    /// Characters typed so far (always lowercase)
+    buffer: String,
+    /// Keys that confirm a label and their actions
+    bindings: KeyBindings,
+    /// Action performed by every bound key, if fixed (e.g., `--action`)
+    action: Option<Action>,
+}
+
+impl InputHandler {
+    /// Create a new handler with an empty input buffer
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a handler with the given key bindings
+    pub fn with_bindings(bindings: KeyBindings) -> Self {
+        InputHandler {
+            bindings,
+            ..Self::default()
+        }
+    }
+
+    /// Make every bound key perform `action`
+    ///
+    /// Keybinds launching HyprGrid for a single purpose (e.g., right click)
+    /// use this, so any confirm key will do.
+    pub fn set_action(&mut self, action: Action) {
+        self.action = Some(action);
+    }
+
+    /// Get the characters typed so far
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Get the current stage of the state machine
+    pub fn state(&self, grid: &Grid) -> InputState {
+        if self.buffer.is_empty() {
+            InputState::FirstChar
+        } else if grid.has_label(&self.buffer) {
+            InputState::ActionKey
+        } else {
+            InputState::NextChar
+        }
+    }
+
+    /// Get the only cell still matching the typed characters, if any
+    ///
+    /// This is the typed label once it is complete, but also a longer label
+    /// that is the sole match of a shorter prefix. Either way the selection
+    /// is unambiguous and can be confirmed.
+    pub fn candidate<'a>(&self, grid: &'a Grid) -> Option<&'a GridCell> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let mut matching = grid.cells_with_prefix(&self.buffer);
+        let first = matching.next()?;
+        matching.next().is_none().then_some(first)
+    }
+
+    /// Clear the input buffer and start over
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Feed a key event to the state machine
+    ///
+    /// # Arguments
+    /// * `key` - The key that was pressed
+    /// * `grid` - The grid used to validate label prefixes
+    ///
+    /// # Returns
+    /// An [`InputOutcome`] describing how the key was handled
+    ///
+    /// # Transitions
+    /// - Letters extend the buffer if the result is a prefix of some label
+    /// - BACKSPACE removes the last character (stepping back one state)
+    /// - ESC cancels the interaction from any state
+    /// - Bound keys (by default SPACE for left click and ENTER for right click)
+    ///   complete a full label, or a prefix that only one label starts with,
+    ///   with their action (or the one given to [`set_action`](Self::set_action))
+    /// - TAB on such a label requests refinement into a sub-grid
+    pub fn handle_key(&mut self, key: Key, grid: &Grid) -> InputOutcome {
+        match key {
+            Key::Escape => InputOutcome::Cancelled,
+            Key::Backspace => {
+                if self.buffer.pop().is_some() {
+                    InputOutcome::Updated
+                } else {
+                    InputOutcome::Ignored
+                }
+            }
+            Key::Char(c) if c.is_ascii_alphabetic() => {
+                if self.state(grid) == InputState::ActionKey {
+                    return InputOutcome::Rejected;
+                }
+
+                let mut candidate = self.buffer.clone();
+                candidate.push(c.to_ascii_lowercase());
+
+                if grid.has_prefix(&candidate) {
+                    self.buffer = candidate;
+                    InputOutcome::Updated
+                } else {
+                    InputOutcome::Rejected
+                }
+            }
+            Key::Tab => match self.candidate(grid) {
+                Some(cell) => InputOutcome::Refine(cell.label.clone()),
+                None => InputOutcome::Ignored,
+            },
+            _ => match self.bindings.action(key) {
+                Some(action) => self.select(action, grid),
+                None => InputOutcome::Ignored,
+            },
+        }
+    }
+
+    /// Complete the selection if the typed characters identify a single cell
+    fn select(&mut self, action: Action, grid: &Grid) -> InputOutcome {
+        match self.candidate(grid) {
+            Some(cell) => InputOutcome::Selected(Selection {
+                label: cell.label.clone(),
+                action: self.action.unwrap_or(action),
+            }),
+            None => InputOutcome::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{CellCoordinates, LabelMode, LabelOptions};
+
+    fn type_keys(handler: &mut InputHandler, grid: &Grid, keys: &[Key]) -> InputOutcome {
+        let mut outcome = InputOutcome::Ignored;
+        for key in keys {
+            outcome = handler.handle_key(*key, grid);
+        }
+        outcome
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let handler = InputHandler::new();
+        assert_eq!(handler.state(&grid), InputState::FirstChar);
+        assert_eq!(handler.buffer(), "");
+    }
+
+    #[test]
+    fn test_left_click_selection() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        let outcome = type_keys(
+            &mut handler,
+            &grid,
+            &[Key::Char('a'), Key::Char('s'), Key::Space],
+        );
+
+        assert_eq!(
+            outcome,
+            InputOutcome::Selected(Selection {
+                label: "as".to_string(),
+                action: Action::LeftClick,
+            })
+        );
+    }
+
+    #[test]
+    fn test_right_click_selection() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        let outcome = type_keys(
+            &mut handler,
+            &grid,
+            &[Key::Char('a'), Key::Char('d'), Key::Enter],
+        );
+
+        assert_eq!(
+            outcome,
+            InputOutcome::Selected(Selection {
+                label: "ad".to_string(),
+                action: Action::RightClick,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fixed_action() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+
+        for confirm in [Key::Space, Key::Enter, Key::Char('2')] {
+            let mut handler = InputHandler::new();
+            handler.set_action(Action::RightClick);
+            let outcome = type_keys(&mut handler, &grid, &[Key::Char('a'), Key::Char('s'), confirm]);
+            assert_eq!(
+                outcome,
+                InputOutcome::Selected(Selection {
+                    label: "as".to_string(),
+                    action: Action::RightClick,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_action_names() {
+        assert_eq!(Action::from_name("left-click"), Some(Action::LeftClick));
+        assert_eq!(Action::from_name("Right-Click"), Some(Action::RightClick));
+        assert_eq!(Action::from_name("click"), None);
+        assert_eq!(Action::from_name("move"), Some(Action::Move));
+        assert!(Action::names().starts_with("left-click, right-click, middle-click"));
+    }
+
+    #[test]
+    fn test_default_key_bindings() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let cases = [
+            (Key::Space, Some(Action::LeftClick)),
+            (Key::Enter, Some(Action::RightClick)),
+            (Key::Char(','), Some(Action::MiddleClick)),
+            (Key::Char('2'), Some(Action::DoubleClick)),
+            (Key::Char('3'), Some(Action::TripleClick)),
+            (Key::Char('.'), Some(Action::Move)),
+            (Key::Char('/'), Some(Action::Drag)),
+            (Key::Char('1'), None),
+            (Key::Other, None),
+        ];
+
+        for (key, expected) in cases {
+            let mut handler = InputHandler::new();
+            let outcome = type_keys(&mut handler, &grid, &[Key::Char('a'), Key::Char('s'), key]);
+            let expected = match expected {
+                Some(action) => InputOutcome::Selected(Selection {
+                    label: "as".to_string(),
+                    action,
+                }),
+                None => InputOutcome::Ignored,
+            };
+            assert_eq!(outcome, expected, "key {:?}", key);
+        }
+    }
+
+    #[test]
+    fn test_custom_key_bindings() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut bindings = KeyBindings::default();
+        bindings.bind(Key::Space, Action::CtrlClick);
+        bindings.bind(Key::Char('!'), Action::ShiftClick);
+
+        let mut handler = InputHandler::with_bindings(bindings);
+        handler.handle_key(Key::Char('a'), &grid);
+        handler.handle_key(Key::Char('s'), &grid);
+        for (key, action) in [(Key::Space, Action::CtrlClick), (Key::Char('!'), Action::ShiftClick)] {
+            match handler.handle_key(key, &grid) {
+                InputOutcome::Selected(selection) => assert_eq!(selection.action, action),
+                outcome => panic!("unexpected outcome {:?}", outcome),
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_names() {
+        assert_eq!(Key::from_name("space"), Some(Key::Space));
+        assert_eq!(Key::from_name("Return"), Some(Key::Enter));
+        assert_eq!(Key::from_name("2"), Some(Key::Char('2')));
+        assert_eq!(Key::from_name(";"), Some(Key::Char(';')));
+        assert_eq!(Key::from_name("f1"), None);
+        assert_eq!(Key::from_name(" "), None);
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        handler.handle_key(Key::Char('a'), &grid);
+        assert_eq!(handler.state(&grid), InputState::NextChar);
+
+        handler.handle_key(Key::Char('f'), &grid);
+        assert_eq!(handler.state(&grid), InputState::ActionKey);
+        assert_eq!(handler.buffer(), "af");
+    }
+
+    #[test]
+    fn test_uppercase_is_lowercased() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        handler.handle_key(Key::Char('A'), &grid);
+        handler.handle_key(Key::Char('S'), &grid);
+        assert_eq!(handler.buffer(), "as");
+    }
+
+    #[test]
+    fn test_invalid_prefix_rejected() {
+        // 2×3 grid only has labels starting with 'a'
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        assert_eq!(handler.handle_key(Key::Char('z'), &grid), InputOutcome::Rejected);
+        assert_eq!(handler.buffer(), "");
+
+        handler.handle_key(Key::Char('a'), &grid);
+        // "az" is not a label in a 6-cell grid
+        assert_eq!(handler.handle_key(Key::Char('z'), &grid), InputOutcome::Rejected);
+        assert_eq!(handler.buffer(), "a");
+    }
+
+    #[test]
+    fn test_extra_letter_after_complete_label_rejected() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        type_keys(&mut handler, &grid, &[Key::Char('a'), Key::Char('s')]);
+        assert_eq!(handler.handle_key(Key::Char('a'), &grid), InputOutcome::Rejected);
+        assert_eq!(handler.buffer(), "as");
+    }
+
+    #[test]
+    fn test_action_before_complete_label_ignored() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        assert_eq!(handler.handle_key(Key::Space, &grid), InputOutcome::Ignored);
+        handler.handle_key(Key::Char('a'), &grid);
+        assert_eq!(handler.handle_key(Key::Enter, &grid), InputOutcome::Ignored);
+        assert_eq!(handler.state(&grid), InputState::NextChar);
+    }
+
+    #[test]
+    fn test_backspace_steps_back() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        type_keys(&mut handler, &grid, &[Key::Char('a'), Key::Char('s')]);
+        assert_eq!(handler.state(&grid), InputState::ActionKey);
+
+        assert_eq!(handler.handle_key(Key::Backspace, &grid), InputOutcome::Updated);
+        assert_eq!(handler.state(&grid), InputState::NextChar);
+
+        assert_eq!(handler.handle_key(Key::Backspace, &grid), InputOutcome::Updated);
+        assert_eq!(handler.state(&grid), InputState::FirstChar);
+
+        // Backspace on an empty buffer does nothing
+        assert_eq!(handler.handle_key(Key::Backspace, &grid), InputOutcome::Ignored);
+    }
+
+    #[test]
+    fn test_escape_cancels_from_any_state() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+
+        let mut handler = InputHandler::new();
+        assert_eq!(handler.handle_key(Key::Escape, &grid), InputOutcome::Cancelled);
+
+        let mut handler = InputHandler::new();
+        handler.handle_key(Key::Char('a'), &grid);
+        assert_eq!(handler.handle_key(Key::Escape, &grid), InputOutcome::Cancelled);
+
+        let mut handler = InputHandler::new();
+        type_keys(&mut handler, &grid, &[Key::Char('a'), Key::Char('s')]);
+        assert_eq!(handler.handle_key(Key::Escape, &grid), InputOutcome::Cancelled);
+    }
+
+    #[test]
+    fn test_other_keys_ignored() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        assert_eq!(handler.handle_key(Key::Other, &grid), InputOutcome::Ignored);
+        assert_eq!(handler.handle_key(Key::Char('1'), &grid), InputOutcome::Ignored);
+        assert_eq!(handler.state(&grid), InputState::FirstChar);
+    }
+
+    #[test]
+    fn test_tab_requests_refinement() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        // Not available before a full label is typed
+        handler.handle_key(Key::Char('a'), &grid);
+        assert_eq!(handler.handle_key(Key::Tab, &grid), InputOutcome::Ignored);
+
+        handler.handle_key(Key::Char('g'), &grid);
+        assert_eq!(
+            handler.handle_key(Key::Tab, &grid),
+            InputOutcome::Refine("ag".to_string())
+        );
+
+        // Continue typing in the sub-grid after a reset
+        let sub = grid.subdivide("ag", 3, 3).unwrap();
+        handler.reset();
+        let outcome = type_keys(&mut handler, &sub, &[Key::Char('a'), Key::Char('d'), Key::Space]);
+        assert_eq!(
+            outcome,
+            InputOutcome::Selected(Selection {
+                label: "ad".to_string(),
+                action: Action::LeftClick,
+            })
+        );
+    }
+
+    #[test]
+    fn test_single_candidate_confirms_prefix() {
+        // In a single-column coordinate grid the row letter alone picks a cell
+        let options = LabelOptions {
+            mode: LabelMode::Coordinates,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 100, height: 300 };
+        let grid = Grid::for_area(3, 1, area, &options).unwrap();
+        let mut handler = InputHandler::new();
+
+        handler.handle_key(Key::Char('s'), &grid);
+        assert_eq!(handler.state(&grid), InputState::NextChar);
+        assert_eq!(
+            handler.handle_key(Key::Enter, &grid),
+            InputOutcome::Selected(Selection {
+                label: "sa".to_string(),
+                action: Action::RightClick,
+            })
+        );
+        assert_eq!(
+            handler.handle_key(Key::Tab, &grid),
+            InputOutcome::Refine("sa".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reset_clears_buffer() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let mut handler = InputHandler::new();
+
+        handler.handle_key(Key::Char('a'), &grid);
+        handler.reset();
+        assert_eq!(handler.buffer(), "");
+        assert_eq!(handler.state(&grid), InputState::FirstChar);
+    }
+
+    #[test]
+    fn test_three_letter_labels() {
+        // 27×27 cells do not fit in two letters, so the last labels have three
+        let grid = Grid::new(27, 27, 2700, 2700).unwrap();
+        let long = grid.cell_at(26, 26).unwrap().label.clone();
+        assert_eq!(long.len(), 3);
+
+        let mut handler = InputHandler::new();
+        let keys: Vec<Key> = long.chars().map(Key::Char).collect();
+
+        type_keys(&mut handler, &grid, &keys[..2]);
+        assert_eq!(handler.state(&grid), InputState::NextChar);
+
+        type_keys(&mut handler, &grid, &keys[2..]);
+        assert_eq!(handler.state(&grid), InputState::ActionKey);
+        assert_eq!(
+            handler.handle_key(Key::Space, &grid),
+            InputOutcome::Selected(Selection {
+                label: long,
+                action: Action::LeftClick,
+            })
+        );
+    }
+}
@@ -0,0 +1,847 @@
+// Layer-shell module for HyprGrid
+//
+// This module shows the overlay as a wlr-layer-shell surface: it sits on the
+// overlay layer above every window, covers exactly one output, and takes
+// exclusive keyboard focus, without any compositor window rules. Frames are
+// drawn in software with tiny-skia and cosmic-text into shared memory, and
+// key codes are translated with the compositor's own xkb keymap.
+
+use anyhow::{anyhow, Context, Result};
+use cosmic_text::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping, SwashCache};
+use iced::{Color, Size};
+use std::ffi::CString;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::fd::AsFd;
+use std::os::unix::fs::FileExt;
+use std::path::PathBuf;
+use tiny_skia::{Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+use wayland_client::protocol::{
+    wl_buffer, wl_compositor, wl_keyboard, wl_output, wl_registry, wl_seat, wl_shm, wl_shm_pool,
+    wl_surface,
+};
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum};
+use wayland_protocols_wlr::layer_shell::v1::client::{zwlr_layer_shell_v1, zwlr_layer_surface_v1};
+use xkbcommon_dl::{keysyms, xkb_context, xkb_keymap, xkb_state, XkbCommon};
+
+use crate::input::Key;
+use crate::overlay::{FontFamily, Overlay, Shape};
+
+/// Namespace reported to the compositor (usable in Hyprland layer rules)
+const NAMESPACE: &str = "hyprgrid";
+
+/// A connection to a compositor that supports wlr-layer-shell
+pub struct LayerShell {
+    connection: Connection,
+    queue: EventQueue<State>,
+    state: State,
+}
+
+impl LayerShell {
+    /// Connect to the compositor and check that everything needed is there
+    ///
+    /// Nothing is shown yet, so on error the caller can still fall back to a
+    /// regular window.
+    ///
+    /// # Arguments
+    /// * `output` - Name of the output to cover (e.g., "DP-1")
+    ///
+    /// # Returns
+    /// - `Ok(LayerShell)` ready to [`run`](Self::run)
+    /// - `Err` if there is no Wayland session, the compositor lacks the
+    ///   layer-shell protocol, libxkbcommon is missing, or no output has the
+    ///   given name (outputs older than `wl_output` version 4 are unnamed)
+    pub fn connect(output: &str) -> Result<Self> {
+        let xkb = xkbcommon_dl::xkbcommon_option()
+            .ok_or_else(|| anyhow!("libxkbcommon could not be loaded"))?;
+
+        let connection =
+            Connection::connect_to_env().context("Failed to connect to the Wayland compositor")?;
+        let mut queue = connection.new_event_queue();
+        let qh = queue.handle();
+        connection.display().get_registry(&qh, ());
+
+        let mut state = State::new(xkb, output);
+        // The first roundtrip announces the globals, the second their details
+        // (such as output names)
+        queue.roundtrip(&mut state)?;
+        queue.roundtrip(&mut state)?;
+
+        if state.layer_shell.is_none() {
+            return Err(anyhow!(
+                "The compositor does not support the wlr-layer-shell protocol"
+            ));
+        }
+        if state.compositor.is_none() || state.shm.is_none() || state.seat.is_none() {
+            return Err(anyhow!(
+                "The compositor is missing wl_compositor, wl_shm or wl_seat"
+            ));
+        }
+        // Without the output the compositor would pick one, and the overlay
+        // could land on another monitor than the one the grid was built for
+        if state.output().is_none() {
+            let names: Vec<&str> = state.outputs.iter().filter_map(|o| o.name.as_deref()).collect();
+            return Err(anyhow!(
+                "No Wayland output is named {}. Named outputs: {}.\n\
+                 Outputs older than wl_output version 4 do not announce their names.",
+                output,
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            ));
+        }
+
+        Ok(LayerShell {
+            connection,
+            queue,
+            state,
+        })
+    }
+
+    /// Show the overlay and process input until it is done
+    ///
+    /// The surface is destroyed before returning, so pointer actions performed
+    /// afterwards reach the windows underneath.
+    pub fn run(mut self, overlay: Overlay) -> Result<()> {
+        let qh = self.queue.handle();
+        self.state.overlay = Some(overlay);
+        self.state.create_surface(&qh);
+
+        while !self.state.done {
+            self.queue.blocking_dispatch(&mut self.state)?;
+            if let Some(error) = self.state.error.take() {
+                return Err(error);
+            }
+            if self.state.needs_redraw && !self.state.done {
+                self.state.draw(&qh)?;
+            }
+        }
+
+        self.state.destroy_surface();
+        self.queue.roundtrip(&mut self.state)?;
+        self.connection.flush()?;
+        Ok(())
+    }
+}
+
+/// An output advertised by the compositor
+struct Output {
+    output: wl_output::WlOutput,
+    name: Option<String>,
+    /// Integer scale factor (fractional scales are rounded up)
+    scale: i32,
+}
+
+/// Wayland client state
+struct State {
+    compositor: Option<wl_compositor::WlCompositor>,
+    shm: Option<wl_shm::WlShm>,
+    seat: Option<wl_seat::WlSeat>,
+    layer_shell: Option<zwlr_layer_shell_v1::ZwlrLayerShellV1>,
+    outputs: Vec<Output>,
+    /// Name of the output to cover
+    output_name: String,
+
+    surface: Option<wl_surface::WlSurface>,
+    layer_surface: Option<zwlr_layer_surface_v1::ZwlrLayerSurfaceV1>,
+    keyboard: Option<wl_keyboard::WlKeyboard>,
+    /// Size assigned by the compositor, in surface coordinates
+    size: (u32, u32),
+    /// Scale of the covered output; buffers are rendered this much larger
+    scale: i32,
+    /// The file backing the buffer currently attached to the surface
+    buffer_file: Option<File>,
+
+    xkb: &'static XkbCommon,
+    keymap: Option<Keymap>,
+    painter: Option<Painter>,
+    overlay: Option<Overlay>,
+
+    configured: bool,
+    needs_redraw: bool,
+    done: bool,
+    error: Option<anyhow::Error>,
+}
+
+impl State {
+    fn new(xkb: &'static XkbCommon, output_name: &str) -> Self {
+        State {
+            compositor: None,
+            shm: None,
+            seat: None,
+            layer_shell: None,
+            outputs: Vec::new(),
+            output_name: output_name.to_string(),
+            surface: None,
+            layer_surface: None,
+            keyboard: None,
+            size: (0, 0),
+            scale: 1,
+            buffer_file: None,
+            xkb,
+            keymap: None,
+            painter: None,
+            overlay: None,
+            configured: false,
+            needs_redraw: false,
+            done: false,
+            error: None,
+        }
+    }
+
+    /// The output to cover, if the compositor announced one with its name
+    fn output(&self) -> Option<&Output> {
+        let index = output_index(self.outputs.iter().map(|o| o.name.as_deref()), &self.output_name)?;
+        Some(&self.outputs[index])
+    }
+
+    /// Create the layer surface covering the chosen output
+    fn create_surface(&mut self, qh: &QueueHandle<State>) {
+        let (Some(compositor), Some(layer_shell)) = (&self.compositor, &self.layer_shell) else {
+            return;
+        };
+        let output = self.output().expect("the output is checked by LayerShell::connect");
+        let scale = output.scale.max(1);
+        let surface = compositor.create_surface(qh, ());
+
+        let layer_surface = layer_shell.get_layer_surface(
+            &surface,
+            Some(&output.output),
+            zwlr_layer_shell_v1::Layer::Overlay,
+            NAMESPACE.to_string(),
+            qh,
+            (),
+        );
+        layer_surface.set_anchor(
+            zwlr_layer_surface_v1::Anchor::Top
+                | zwlr_layer_surface_v1::Anchor::Bottom
+                | zwlr_layer_surface_v1::Anchor::Left
+                | zwlr_layer_surface_v1::Anchor::Right,
+        );
+        // Cover bars and docks too instead of being laid out around them: the
+        // surface origin is the monitor origin the overlay draws relative to,
+        // whether or not the grid itself leaves reserved strips uncovered
+        layer_surface.set_exclusive_zone(-1);
+        layer_surface
+            .set_keyboard_interactivity(zwlr_layer_surface_v1::KeyboardInteractivity::Exclusive);
+        surface.commit();
+
+        self.scale = scale;
+        self.surface = Some(surface);
+        self.layer_surface = Some(layer_surface);
+    }
+
+    fn destroy_surface(&mut self) {
+        if let Some(keyboard) = self.keyboard.take() {
+            if keyboard.version() >= 3 {
+                keyboard.release();
+            }
+        }
+        if let Some(layer_surface) = self.layer_surface.take() {
+            layer_surface.destroy();
+        }
+        if let Some(surface) = self.surface.take() {
+            surface.destroy();
+        }
+    }
+
+    /// Render the current scene and attach it to the surface
+    fn draw(&mut self, qh: &QueueHandle<State>) -> Result<()> {
+        self.needs_redraw = false;
+        let (Some(overlay), Some(surface), Some(shm)) = (&self.overlay, &self.surface, &self.shm)
+        else {
+            return Ok(());
+        };
+
+        // The scene is laid out in surface coordinates and rendered at the
+        // output's scale, so text stays sharp on HiDPI outputs
+        let (width, height) = self.size;
+        let scene = overlay.scene(Size::new(width as f32, height as f32));
+        let scale = self.scale;
+        let (width, height) = (width * scale as u32, height * scale as u32);
+        let painter = self.painter.get_or_insert_with(Painter::new);
+        let pixmap = painter
+            .render(&scene, width, height, scale as f32)
+            .ok_or_else(|| anyhow!("Invalid overlay size {}x{}", width, height))?;
+
+        let mut file = shm_file()?;
+        file.write_all(&to_argb8888(pixmap.data()))
+            .context("Failed to write the overlay frame to shared memory")?;
+
+        let stride = width as i32 * 4;
+        let pool = shm.create_pool(file.as_fd(), stride * height as i32, qh, ());
+        let buffer = pool.create_buffer(
+            0,
+            width as i32,
+            height as i32,
+            stride,
+            wl_shm::Format::Argb8888,
+            qh,
+            (),
+        );
+        pool.destroy();
+
+        surface.set_buffer_scale(scale);
+        surface.attach(Some(&buffer), 0, 0);
+        surface.damage_buffer(0, 0, width as i32, height as i32);
+        surface.commit();
+        self.buffer_file = Some(file);
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: Key) {
+        if let Some(overlay) = &mut self.overlay {
+            if overlay.handle_key(key) {
+                self.done = true;
+            } else {
+                self.needs_redraw = self.configured;
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for State {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        else {
+            return;
+        };
+
+        match interface.as_str() {
+            "wl_compositor" => state.compositor = Some(registry.bind(name, version.min(4), qh, ())),
+            "wl_shm" => state.shm = Some(registry.bind(name, 1, qh, ())),
+            "wl_seat" if state.seat.is_none() => {
+                state.seat = Some(registry.bind(name, version.min(5), qh, ()))
+            }
+            // Version 4 adds the output name
+            "wl_output" => state.outputs.push(Output {
+                output: registry.bind(name, version.min(4), qh, ()),
+                name: None,
+                scale: 1,
+            }),
+            "zwlr_layer_shell_v1" => {
+                state.layer_shell = Some(registry.bind(name, version.min(4), qh, ()))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Find the position of the output with the given name
+///
+/// Outputs that announced no name (older than version 4) never match.
+fn output_index<'a>(names: impl IntoIterator<Item = Option<&'a str>>, name: &str) -> Option<usize> {
+    names.into_iter().position(|n| n == Some(name))
+}
+
+impl Dispatch<wl_output::WlOutput, ()> for State {
+    fn event(
+        state: &mut Self,
+        output: &wl_output::WlOutput,
+        event: wl_output::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(known) = state.outputs.iter_mut().find(|o| &o.output == output) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Name { name } => known.name = Some(name),
+            wl_output::Event::Scale { factor } => known.scale = factor,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for State {
+    fn event(
+        state: &mut Self,
+        seat: &wl_seat::WlSeat,
+        event: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+        {
+            if capabilities.contains(wl_seat::Capability::Keyboard) && state.keyboard.is_none() {
+                state.keyboard = Some(seat.get_keyboard(qh, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_keyboard::WlKeyboard, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &wl_keyboard::WlKeyboard,
+        event: wl_keyboard::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_keyboard::Event::Keymap {
+                format: WEnum::Value(wl_keyboard::KeymapFormat::XkbV1),
+                fd,
+                size,
+            } => {
+                let mut text = vec![0; size as usize];
+                let keymap = File::from(fd)
+                    .read_exact_at(&mut text, 0)
+                    .context("Failed to read the keyboard keymap")
+                    .and_then(|_| Keymap::new(state.xkb, &text));
+                match keymap {
+                    Ok(keymap) => state.keymap = Some(keymap),
+                    Err(e) => state.error = Some(e),
+                }
+            }
+            wl_keyboard::Event::Key {
+                key,
+                state: WEnum::Value(wl_keyboard::KeyState::Pressed),
+                ..
+            } => {
+                if let Some(key) = state.keymap.as_ref().map(|keymap| keymap.key(key)) {
+                    state.handle_key(key);
+                }
+            }
+            wl_keyboard::Event::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => {
+                if let Some(keymap) = &state.keymap {
+                    keymap.update_modifiers(mods_depressed, mods_latched, mods_locked, group);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        layer_surface: &zwlr_layer_surface_v1::ZwlrLayerSurfaceV1,
+        event: zwlr_layer_surface_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_layer_surface_v1::Event::Configure {
+                serial,
+                width,
+                height,
+            } => {
+                layer_surface.ack_configure(serial);
+                state.size = (width, height);
+                state.configured = true;
+                state.needs_redraw = true;
+            }
+            zwlr_layer_surface_v1::Event::Closed => state.done = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_buffer::WlBuffer, ()> for State {
+    fn event(
+        _: &mut Self,
+        buffer: &wl_buffer::WlBuffer,
+        event: wl_buffer::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            buffer.destroy();
+        }
+    }
+}
+
+delegate_noop!(State: ignore wl_compositor::WlCompositor);
+delegate_noop!(State: ignore wl_surface::WlSurface);
+delegate_noop!(State: ignore wl_shm::WlShm);
+delegate_noop!(State: ignore wl_shm_pool::WlShmPool);
+delegate_noop!(State: ignore zwlr_layer_shell_v1::ZwlrLayerShellV1);
+
+/// The compositor's keymap, loaded into libxkbcommon
+struct Keymap {
+    xkb: &'static XkbCommon,
+    context: *mut xkb_context,
+    keymap: *mut xkb_keymap,
+    state: *mut xkb_state,
+}
+
+impl Keymap {
+    /// Compile a keymap sent by the compositor (XKB text format)
+    fn new(xkb: &'static XkbCommon, text: &[u8]) -> Result<Self> {
+        // The keymap is NUL-terminated; CString adds its own terminator
+        let end = text.iter().position(|&b| b == 0).unwrap_or(text.len());
+        let text = CString::new(&text[..end])?;
+
+        unsafe {
+            let context = (xkb.xkb_context_new)(xkbcommon_dl::xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            if context.is_null() {
+                return Err(anyhow!("Failed to create an xkb context"));
+            }
+            let keymap = (xkb.xkb_keymap_new_from_string)(
+                context,
+                text.as_ptr(),
+                xkbcommon_dl::xkb_keymap_format::XKB_KEYMAP_FORMAT_TEXT_V1,
+                xkbcommon_dl::xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            if keymap.is_null() {
+                (xkb.xkb_context_unref)(context);
+                return Err(anyhow!("Failed to compile the compositor's keymap"));
+            }
+            let state = (xkb.xkb_state_new)(keymap);
+            if state.is_null() {
+                (xkb.xkb_keymap_unref)(keymap);
+                (xkb.xkb_context_unref)(context);
+                return Err(anyhow!("Failed to create an xkb state"));
+            }
+
+            Ok(Keymap {
+                xkb,
+                context,
+                keymap,
+                state,
+            })
+        }
+    }
+
+    fn update_modifiers(&self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        unsafe {
+            (self.xkb.xkb_state_update_mask)(self.state, depressed, latched, locked, 0, 0, group);
+        }
+    }
+
+    /// Translate a Wayland (evdev) key code
+    fn key(&self, code: u32) -> Key {
+        // XKB key codes are evdev codes offset by 8
+        let code = code + 8;
+        let (keysym, utf32) = unsafe {
+            (
+                (self.xkb.xkb_state_key_get_one_sym)(self.state, code),
+                (self.xkb.xkb_state_key_get_utf32)(self.state, code),
+            )
+        };
+        key_from_keysym(keysym, utf32)
+    }
+}
+
+impl Drop for Keymap {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xkb.xkb_state_unref)(self.state);
+            (self.xkb.xkb_keymap_unref)(self.keymap);
+            (self.xkb.xkb_context_unref)(self.context);
+        }
+    }
+}
+
+/// Map an XKB keysym (and the character it produces) to an input key
+fn key_from_keysym(keysym: u32, utf32: u32) -> Key {
+    match keysym {
+        keysyms::Escape => Key::Escape,
+        keysyms::Return | keysyms::KP_Enter => Key::Enter,
+        keysyms::BackSpace => Key::Backspace,
+        keysyms::Tab | keysyms::ISO_Left_Tab => Key::Tab,
+        keysyms::space => Key::Space,
+        _ => match char::from_u32(utf32) {
+            Some(c) if !c.is_control() && c != '\0' => Key::Char(c),
+            _ => Key::Other,
+        },
+    }
+}
+
+/// Software renderer for overlay scenes
+struct Painter {
+    fonts: FontSystem,
+    glyphs: SwashCache,
+}
+
+impl Painter {
+    fn new() -> Self {
+        Painter {
+            fonts: FontSystem::new(),
+            glyphs: SwashCache::new(),
+        }
+    }
+
+    /// Draw a scene into a new pixmap
+    ///
+    /// `width` and `height` are the pixmap size in buffer pixels; the scene
+    /// is in surface coordinates and is scaled up by `scale`.
+    ///
+    /// # Returns
+    /// `None` if the size is empty
+    fn render(&mut self, scene: &[Shape], width: u32, height: u32, scale: f32) -> Option<Pixmap> {
+        let mut pixmap = Pixmap::new(width, height)?;
+        let transform = Transform::from_scale(scale, scale);
+
+        for shape in scene {
+            match shape {
+                Shape::Fill { rect, color } => {
+                    if let Some(rect) = Rect::from_xywh(rect.x, rect.y, rect.width, rect.height) {
+                        pixmap.fill_rect(rect, &paint(*color), transform, None);
+                    }
+                }
+                Shape::Outline { rect, color, width } => {
+                    if let Some(rect) = Rect::from_xywh(rect.x, rect.y, rect.width, rect.height) {
+                        let path = PathBuilder::from_rect(rect);
+                        let stroke = Stroke {
+                            width: *width,
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(&path, &paint(*color), &stroke, transform, None);
+                    }
+                }
+                Shape::Label { .. } => self.label(&mut pixmap, shape, scale),
+            }
+        }
+
+        Some(pixmap)
+    }
+
+    /// Draw a label shape: text centered in its rectangle
+    ///
+    /// The first `count` characters of a highlighted label get their own color.
+    fn label(&mut self, pixmap: &mut Pixmap, label: &Shape, scale: f32) {
+        let Shape::Label {
+            text,
+            rect,
+            font,
+            size,
+            color,
+            highlight,
+        } = label
+        else {
+            return;
+        };
+        let center = rect.center();
+        let center = iced::Point::new(center.x * scale, center.y * scale);
+        let (size, color, highlight) = (*size * scale, *color, *highlight);
+
+        let family = match font {
+            FontFamily::Monospace => Family::Monospace,
+            FontFamily::SansSerif => Family::SansSerif,
+            FontFamily::Serif => Family::Serif,
+            FontFamily::Named(name) => Family::Name(name),
+        };
+        let attrs = Attrs::new().family(family);
+        let typed_end = highlight.map_or(0, |(count, _)| {
+            text.char_indices().nth(count).map_or(text.len(), |(i, _)| i)
+        });
+        let (typed, rest) = text.split_at(typed_end);
+        let typed_attrs = match highlight {
+            Some((_, highlight_color)) => attrs.color(text_color(highlight_color)),
+            None => attrs,
+        };
+
+        let mut buffer = Buffer::new(&mut self.fonts, Metrics::new(size, size * 1.2));
+        buffer.set_size(&mut self.fonts, None, None);
+        buffer.set_rich_text(
+            &mut self.fonts,
+            [(typed, typed_attrs), (rest, attrs)],
+            attrs,
+            Shaping::Advanced,
+        );
+
+        let (text_width, text_height) = buffer
+            .layout_runs()
+            .fold((0.0f32, 0.0f32), |(w, h), run| (w.max(run.line_w), h + run.line_height));
+        let left = (center.x - text_width / 2.0).round() as i32;
+        let top = (center.y - text_height / 2.0).round() as i32;
+
+        buffer.draw(
+            &mut self.fonts,
+            &mut self.glyphs,
+            text_color(color),
+            |x, y, w, h, color| blend_rect(pixmap, left + x, top + y, w, h, color.as_rgba()),
+        );
+    }
+}
+
+/// Convert a color for cosmic-text
+fn text_color(color: Color) -> cosmic_text::Color {
+    let [r, g, b, a] = color.into_rgba8();
+    cosmic_text::Color::rgba(r, g, b, a)
+}
+
+/// A solid paint for a non-premultiplied color
+fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8,
+        (color.a * 255.0).round() as u8,
+    );
+    paint.anti_alias = false;
+    paint
+}
+
+/// Blend a non-premultiplied RGBA color over a rectangle of pixels
+fn blend_rect(pixmap: &mut Pixmap, x: i32, y: i32, w: u32, h: u32, rgba: [u8; 4]) {
+    let alpha = rgba[3] as u32;
+    if alpha == 0 {
+        return;
+    }
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let data = pixmap.data_mut();
+
+    for py in y.max(0)..(y + h as i32).min(height) {
+        for px in x.max(0)..(x + w as i32).min(width) {
+            let i = (py * width + px) as usize * 4;
+            for c in 0..3 {
+                let src = rgba[c] as u32 * alpha / 255;
+                data[i + c] = (src + data[i + c] as u32 * (255 - alpha) / 255) as u8;
+            }
+            data[i + 3] = (alpha + data[i + 3] as u32 * (255 - alpha) / 255) as u8;
+        }
+    }
+}
+
+/// Convert premultiplied RGBA bytes to Wayland's ARGB8888 (BGRA in memory)
+fn to_argb8888(rgba: &[u8]) -> Vec<u8> {
+    rgba.chunks_exact(4)
+        .flat_map(|p| [p[2], p[1], p[0], p[3]])
+        .collect()
+}
+
+/// Create an anonymous file to share a frame with the compositor
+///
+/// The file is created in `$XDG_RUNTIME_DIR` (a tmpfs) and unlinked right
+/// away, so it disappears as soon as both sides close it.
+fn shm_file() -> Result<File> {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+
+    for attempt in 0..16 {
+        let path = dir.join(format!("hyprgrid-{}-{}.shm", std::process::id(), attempt));
+        match OpenOptions::new().read(true).write(true).create_new(true).open(&path) {
+            Ok(file) => {
+                let _ = std::fs::remove_file(&path);
+                return Ok(file);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to create shared memory file in {}", dir.display())
+                })
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "Failed to create shared memory file in {}",
+        dir.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::{Point, Rectangle};
+
+    #[test]
+    fn test_key_from_keysym() {
+        assert_eq!(key_from_keysym(keysyms::Escape, 0x1b), Key::Escape);
+        assert_eq!(key_from_keysym(keysyms::Return, 0x0d), Key::Enter);
+        assert_eq!(key_from_keysym(keysyms::KP_Enter, 0x0d), Key::Enter);
+        assert_eq!(key_from_keysym(keysyms::BackSpace, 0x08), Key::Backspace);
+        assert_eq!(key_from_keysym(keysyms::ISO_Left_Tab, 0), Key::Tab);
+        assert_eq!(key_from_keysym(keysyms::space, ' ' as u32), Key::Space);
+        assert_eq!(key_from_keysym(keysyms::a, 'a' as u32), Key::Char('a'));
+        // Shifted letters arrive as uppercase and are lowercased later
+        assert_eq!(key_from_keysym(keysyms::A, 'A' as u32), Key::Char('A'));
+        assert_eq!(key_from_keysym(keysyms::Shift_L, 0), Key::Other);
+    }
+
+    #[test]
+    fn test_output_index() {
+        let names = [None, Some("DP-1"), Some("HDMI-A-1")];
+        assert_eq!(output_index(names, "HDMI-A-1"), Some(2));
+        assert_eq!(output_index(names, "DP-1"), Some(1));
+        // Unnamed outputs never match, even a single one
+        assert_eq!(output_index(names, "DP-2"), None);
+        assert_eq!(output_index([None], "DP-1"), None);
+    }
+
+    #[test]
+    fn test_to_argb8888() {
+        assert_eq!(to_argb8888(&[1, 2, 3, 4, 5, 6, 7, 8]), vec![3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn test_blend_rect_clips_and_blends() {
+        let mut pixmap = Pixmap::new(4, 4).unwrap();
+        blend_rect(&mut pixmap, -2, 3, 4, 4, [255, 0, 0, 255]);
+
+        let pixel = |pixmap: &Pixmap, x: u32, y: u32| {
+            let i = ((y * 4 + x) * 4) as usize;
+            pixmap.data()[i..i + 4].to_vec()
+        };
+        assert_eq!(pixel(&pixmap, 0, 3), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 1, 3), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 2, 3), vec![0, 0, 0, 0]);
+        assert_eq!(pixel(&pixmap, 0, 2), vec![0, 0, 0, 0]);
+
+        // Half-transparent white over opaque red
+        blend_rect(&mut pixmap, 0, 3, 1, 1, [255, 255, 255, 128]);
+        assert_eq!(pixel(&pixmap, 0, 3), vec![255, 128, 128, 255]);
+    }
+
+    #[test]
+    fn test_render_shapes() {
+        let scene = vec![
+            Shape::Fill {
+                rect: Rectangle::new(Point::ORIGIN, Size::new(10.0, 10.0)),
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+            },
+            Shape::Outline {
+                rect: Rectangle::new(Point::new(2.0, 2.0), Size::new(6.0, 6.0)),
+                color: Color::WHITE,
+                width: 1.0,
+            },
+        ];
+        let mut painter = Painter {
+            fonts: FontSystem::new_with_fonts([]),
+            glyphs: SwashCache::new(),
+        };
+        let pixmap = painter.render(&scene, 10, 10, 1.0).unwrap();
+        let pixel = |x: u32, y: u32| pixmap.pixel(x, y).unwrap();
+
+        // Background only in the corner, border on the outline, background inside
+        assert_eq!(pixel(0, 0).alpha(), 128);
+        assert_eq!((pixel(2, 5).red(), pixel(2, 5).alpha()), (255, 255));
+        assert_eq!(pixel(5, 5).alpha(), 128);
+
+        assert!(painter.render(&scene, 0, 10, 1.0).is_none());
+
+        // At scale 2 the same scene fills a buffer twice the size
+        let pixmap = painter.render(&scene, 20, 20, 2.0).unwrap();
+        let pixel = |x: u32, y: u32| pixmap.pixel(x, y).unwrap();
+        assert_eq!(pixel(19, 19).alpha(), 128);
+        assert_eq!((pixel(4, 10).red(), pixel(4, 10).alpha()), (255, 255));
+        assert_eq!(pixel(10, 10).alpha(), 128);
+    }
+}
@@ -0,0 +1,188 @@
+// Keyboard layout module for HyprGrid
+//
+// This module describes the physical letter arrangement of common keyboard
+// layouts. Presets are used to derive an ergonomic label alphabet (home row
+// first) for users who do not type on QWERTY.
+
+/// A keyboard layout described by its three letter rows
+///
+/// Each row has ten columns, matching the ten fingers' home positions from the
+/// left pinky to the right pinky. Non-letter keys are written as spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    /// Preset name as used in `hg_config.conf` (e.g., "colemak")
+    pub name: &'static str,
+    /// Top, home and bottom rows
+    rows: [&'static str; 3],
+}
+
+/// Which hand types a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Which finger types a key, assuming standard touch typing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finger {
+    Pinky,
+    Ring,
+    Middle,
+    Index,
+}
+
+/// Where a letter sits on the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPosition {
+    /// 0 = top row, 1 = home row, 2 = bottom row
+    pub row: usize,
+    /// 0 (left pinky) to 9 (right pinky)
+    pub column: usize,
+}
+
+impl KeyPosition {
+    /// The hand typing this key
+    pub fn hand(&self) -> Hand {
+        if self.column < 5 {
+            Hand::Left
+        } else {
+            Hand::Right
+        }
+    }
+
+    /// The finger typing this key (the index fingers cover two columns each)
+    pub fn finger(&self) -> Finger {
+        match self.column {
+            0 | 9 => Finger::Pinky,
+            1 | 8 => Finger::Ring,
+            2 | 7 => Finger::Middle,
+            _ => Finger::Index,
+        }
+    }
+
+    /// Whether the key is in the inner columns, which the index fingers reach
+    /// by stretching sideways
+    pub fn is_stretch(&self) -> bool {
+        self.column == 4 || self.column == 5
+    }
+}
+
+/// Standard QWERTY
+pub const QWERTY: KeyboardLayout = KeyboardLayout {
+    name: "qwerty",
+    rows: ["qwertyuiop", "asdfghjkl ", "zxcvbnm   "],
+};
+
+/// Colemak
+pub const COLEMAK: KeyboardLayout = KeyboardLayout {
+    name: "colemak",
+    rows: ["qwfpgjluy ", "arstdhneio", "zxcvbkm   "],
+};
+
+/// Dvorak (simplified)
+pub const DVORAK: KeyboardLayout = KeyboardLayout {
+    name: "dvorak",
+    rows: ["   pyfgcrl", "aoeuidhtns", " qjkxbmwvz"],
+};
+
+/// Workman
+pub const WORKMAN: KeyboardLayout = KeyboardLayout {
+    name: "workman",
+    rows: ["qdrwbjfup ", "ashtgyneoi", "zxmcvkl   "],
+};
+
+/// All built-in layout presets
+pub const PRESETS: [&KeyboardLayout; 4] = [&QWERTY, &COLEMAK, &DVORAK, &WORKMAN];
+
+impl KeyboardLayout {
+    /// Look up a preset by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<&'static KeyboardLayout> {
+        PRESETS
+            .iter()
+            .copied()
+            .find(|layout| layout.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get the comma-separated list of preset names (for error messages)
+    pub fn preset_names() -> String {
+        PRESETS
+            .iter()
+            .map(|layout| layout.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Find where a letter sits in this layout (case-insensitive)
+    pub fn position(&self, letter: char) -> Option<KeyPosition> {
+        let letter = letter.to_ascii_lowercase();
+        self.rows.iter().enumerate().find_map(|(row, keys)| {
+            keys.chars()
+                .position(|c| c == letter)
+                .map(|column| KeyPosition { row, column })
+        })
+    }
+
+    /// Get the label alphabet for this layout with home row priority
+    ///
+    /// The home row letters come first, in left-to-right order, followed by
+    /// the remaining letters in alphabetical order. For QWERTY this yields
+    /// `asdfghjkl` + `bceimnopqrtuvwxyz`.
+    pub fn home_row_alphabet(&self) -> String {
+        let home: String = self.rows[1].chars().filter(|c| *c != ' ').collect();
+        let rest = ('a'..='z').filter(|c| !home.contains(*c));
+        home.chars().chain(rest).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_cover_alphabet() {
+        for layout in PRESETS {
+            let mut letters: Vec<char> = layout.rows.concat().chars().filter(|c| *c != ' ').collect();
+            letters.sort();
+            let expected: Vec<char> = ('a'..='z').collect();
+            assert_eq!(letters, expected, "layout {} must contain every letter once", layout.name);
+
+            for row in layout.rows {
+                assert_eq!(row.len(), 10, "layout {} rows must have 10 columns", layout.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qwerty_alphabet_matches_default_order() {
+        assert_eq!(QWERTY.home_row_alphabet(), "asdfghjklbceimnopqrtuvwxyz");
+    }
+
+    #[test]
+    fn test_home_row_first() {
+        assert!(COLEMAK.home_row_alphabet().starts_with("arstdhneio"));
+        assert!(DVORAK.home_row_alphabet().starts_with("aoeuidhtns"));
+        assert!(WORKMAN.home_row_alphabet().starts_with("ashtgyneoi"));
+    }
+
+    #[test]
+    fn test_key_position() {
+        let f = QWERTY.position('f').unwrap();
+        assert_eq!(f, KeyPosition { row: 1, column: 3 });
+        assert_eq!((f.hand(), f.finger()), (Hand::Left, Finger::Index));
+
+        let p = QWERTY.position('P').unwrap();
+        assert_eq!((p.row, p.hand(), p.finger()), (0, Hand::Right, Finger::Pinky));
+
+        assert!(QWERTY.position('g').unwrap().is_stretch());
+        assert_eq!(DVORAK.position('h').unwrap(), KeyPosition { row: 1, column: 6 });
+        assert_eq!(QWERTY.position(';'), None);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(KeyboardLayout::from_name("colemak"), Some(&COLEMAK));
+        assert_eq!(KeyboardLayout::from_name("Dvorak"), Some(&DVORAK));
+        assert_eq!(KeyboardLayout::from_name("azerty"), None);
+    }
+}
@@ -1,101 +1,288 @@
+mod cli;
+mod color;
 mod config;
+mod dump;
 mod grid;
+mod hyprland;
+mod input;
+mod layer_shell;
+mod layout;
+mod overlay;
+mod pointer;
 
-use config::{get_active_monitor, GridDimensions, HyprGridConfig};
-use grid::Grid;
+use cli::{Cli, Command, USAGE};
+use config::{
+    get_active_monitor, get_monitor, GridDimensions, HyprGridConfig, MonitorInfo, ReservedArea,
+};
+use grid::{Grid, LabelOptions};
+use std::path::PathBuf;
+use input::Action;
+use pointer::{execute_action, execute_at_label, execute_drag, YdotoolBackend};
 
 fn main() {
-    println!("HyprGrid: Initializing...");
-    println!();
-
-    // Load configuration
-    let config = match HyprGridConfig::load() {
-        Ok(cfg) => cfg,
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
         Err(e) => {
-            eprintln!("Error loading configuration:");
             eprintln!("{:#}", e);
             eprintln!();
-            eprintln!("The Grid remains offline. End of line.");
-            std::process::exit(1);
+            eprint!("{}", USAGE);
+            std::process::exit(2);
         }
     };
+    if cli.help {
+        print!("{}", USAGE);
+        return;
+    }
 
-    // Detect active monitor from Hyprland
-    let active_monitor = match get_active_monitor() {
-        Ok(monitor) => monitor,
-        Err(e) => {
-            eprintln!("Error detecting active monitor:");
-            eprintln!("{:#}", e);
-            eprintln!();
-            eprintln!("The Grid remains offline. End of line.");
-            std::process::exit(1);
+    match &cli.command {
+        Command::CheckConfig => check_config(&cli),
+        Command::Dump => dump_grid(&cli),
+        Command::Click(label) => act_on_label(&cli, label, cli.button.unwrap_or(Action::LeftClick)),
+        Command::Move(label) => act_on_label(&cli, label, Action::Move),
+        Command::Overlay => show_overlay(&cli),
+    }
+}
+
+/// Print an error and exit with status 1
+fn offline(what: &str, e: anyhow::Error) -> ! {
+    eprintln!("Error {}:", what);
+    eprintln!("{:#}", e);
+    eprintln!();
+    eprintln!("The Grid remains offline. End of line.");
+    std::process::exit(1);
+}
+
+/// Load the configuration with the command-line grid size applied
+fn load_config(cli: &Cli) -> (HyprGridConfig, Option<PathBuf>) {
+    HyprGridConfig::load(cli.config.as_deref())
+        .and_then(|(config, path)| Ok((config.with_grid_size(cli.rows, cli.cols)?, path)))
+        .unwrap_or_else(|e| offline("loading configuration", e))
+}
+
+/// Validate the configuration without contacting Hyprland
+fn check_config(cli: &Cli) {
+    let (config, config_path) = load_config(cli);
+
+    println!("Configuration is valid.");
+    match &config_path {
+        Some(path) => println!("  File:     {}", path.display()),
+        None => println!("  File:     none found, using built-in defaults"),
+    }
+    println!("  Grid:     {}x{} (rows x cols)", config.grid_rows, config.grid_cols);
+    if config.monitor.is_empty() {
+        println!("  Monitors: no [monitor.\"...\"] overrides");
+    } else {
+        for key in config.monitor.keys() {
+            println!("  Monitor:  [monitor.\"{}\"]", key);
         }
-    };
+    }
+}
 
-    // Calculate grid dimensions with auto-rotation
-    let grid_dims = GridDimensions::calculate(
-        config.grid_rows,
-        config.grid_cols,
-        &active_monitor,
-    );
-
-    // Generate the complete grid with all cells and coordinates
-    let grid = Grid::new(
-        grid_dims.rows,
-        grid_dims.cols,
-        active_monitor.width,
-        active_monitor.height,
-    );
-
-    // Display configuration report
-    println!("Configuration loaded successfully!");
-    println!();
-    println!("Active Monitor:");
-    println!("  Name:        {}", active_monitor.name);
-    println!("  Resolution:  {}x{}", active_monitor.width, active_monitor.height);
-    println!("  Orientation: {}", grid_dims.orientation.as_str());
-    println!();
-    println!("Grid Configuration:");
-    println!("  Base (landscape): {}x{} (rows x cols)", config.grid_rows, config.grid_cols);
-    println!("  Applied:          {}x{} (rows x cols)", grid_dims.rows, grid_dims.cols);
-    println!("  Total cells:      {}", grid_dims.total_cells());
+/// Write the grid to stdout as JSON or CSV, without the report
+fn dump_grid(cli: &Cli) {
+    let session = Session::start(cli);
+    let orientation = session.grid_dims.orientation;
+    let mut stdout = std::io::stdout().lock();
+    if let Err(e) = dump::write_dump(&mut stdout, cli.format, &session.monitor, orientation, &session.grid) {
+        offline("writing grid dump", e);
+    }
+}
 
-    if grid_dims.orientation.as_str() == "Portrait" {
-        println!();
-        println!("  Note: Grid dimensions auto-rotated for portrait orientation");
+/// Perform an action on a cell given by its label, without the overlay
+fn act_on_label(cli: &Cli, label: &str, action: Action) {
+    let session = Session::start(cli);
+    let mut backend = YdotoolBackend::new();
+    if let Err(e) = execute_at_label(&mut backend, &session.grid, label, action) {
+        offline(&format!("executing action on cell '{}'", label), e);
     }
+}
 
+/// Show the overlay and perform the selected action
+fn show_overlay(cli: &Cli) {
+    println!("HyprGrid: Initializing...");
     println!();
-    println!("Border Settings:");
-    println!("  Enabled:  {}", config.border_enabled);
-    println!("  Color:    {}", config.border_color);
-    println!("  Width:    {} px", config.border_width);
 
-    println!();
-    println!("Grid Calculations:");
-    println!("  Cell width:   {} px", active_monitor.width / grid.cols);
-    println!("  Cell height:  {} px", active_monitor.height / grid.rows);
-    println!("  Total cells:  {}", grid.total_cells());
+    let session = Session::start(cli);
+    session.report();
 
-    // Show a sample of cell labels and their coordinates
     println!();
-    println!("Sample Cell Coordinates:");
-    if let Some(cell) = grid.get_cell("aa") {
-        let (cx, cy) = cell.coordinates.center();
-        println!("  Cell 'aa': position ({}, {}), center at ({}, {})",
-                 cell.coordinates.x, cell.coordinates.y, cx, cy);
-    }
-    if let Some(cell) = grid.get_cell("as") {
-        let (cx, cy) = cell.coordinates.center();
-        println!("  Cell 'as': position ({}, {}), center at ({}, {})",
-                 cell.coordinates.x, cell.coordinates.y, cx, cy);
+    println!("The Grid is online. End of line.");
+
+    // Show the overlay and wait for the user to pick a cell
+    let screen = session.monitor.logical_area();
+    let target = match overlay::run(session.grid, screen, &session.config, &session.monitor, cli.action) {
+        Ok(Some(target)) => target,
+        Ok(None) => {
+            println!("Selection cancelled. End of line.");
+            return;
+        }
+        Err(e) => offline("displaying the grid overlay", e),
+    };
+
+    // The overlay is closed, so the click lands on the window underneath
+    let mut backend = YdotoolBackend::new();
+    let executed = match &target.drag_from {
+        Some(from) => execute_drag(&mut backend, from, &target.area),
+        None => execute_action(&mut backend, target.selection.action, &target.area),
+    };
+    if let Err(e) = executed {
+        eprintln!("Error executing action on cell '{}':", target.selection.label);
+        eprintln!("{:#}", e);
+        std::process::exit(1);
     }
-    if let Some(cell) = grid.get_cell("ad") {
-        let (cx, cy) = cell.coordinates.center();
-        println!("  Cell 'ad': position ({}, {}), center at ({}, {})",
-                 cell.coordinates.x, cell.coordinates.y, cx, cy);
+}
+
+/// The grid of one monitor with the configuration it was built from
+struct Session {
+    config: HyprGridConfig,
+    config_path: Option<PathBuf>,
+    monitor: MonitorInfo,
+    /// Key of the `[monitor."..."]` table applied, if any
+    monitor_override: Option<String>,
+    grid_dims: GridDimensions,
+    label_options: LabelOptions,
+    grid: Grid,
+}
+
+impl Session {
+    /// Load the configuration, detect the monitor and build its grid
+    ///
+    /// Settings are layered as built-in defaults, then the configuration
+    /// file, then the monitor's `[monitor."..."]` table, then the command
+    /// line. Exits with an error message if any step fails.
+    fn start(cli: &Cli) -> Session {
+        // Load configuration (built-in defaults if there is no file)
+        let (config, config_path) = load_config(cli);
+
+        // Detect the monitor from Hyprland: the one given with --monitor, or the focused one
+        let monitor = match &cli.monitor {
+            Some(name) => get_monitor(name),
+            None => get_active_monitor(),
+        };
+        let monitor = monitor.unwrap_or_else(|e| offline("detecting active monitor", e));
+
+        // Apply the monitor's [monitor."..."] overrides, if any, then the command line again
+        let monitor_override = config
+            .monitor_override(&monitor)
+            .map(|(key, _)| key.to_string());
+        let config = config
+            .for_monitor(&monitor)
+            .with_grid_size(cli.rows, cli.cols)
+            .unwrap_or_else(|e| offline("loading configuration", e));
+
+        // Calculate grid dimensions with auto-rotation
+        let grid_dims = GridDimensions::calculate(config.grid_rows, config.grid_cols, &monitor);
+
+        // Generate the complete grid with all cells in global logical coordinates
+        let monitor_area = monitor.logical_area();
+        let label_options = config
+            .label_options()
+            .expect("label options are checked by HyprGridConfig::validate");
+        let grid = match config.reserved_area {
+            ReservedArea::Ignore => {
+                Grid::for_area(grid_dims.rows, grid_dims.cols, monitor_area, &label_options)
+            }
+            ReservedArea::Exclude => Grid::for_area(
+                grid_dims.rows,
+                grid_dims.cols,
+                monitor_area.inset(monitor.reserved),
+                &label_options,
+            ),
+            ReservedArea::Dedicated => Grid::with_reserved_strips(
+                grid_dims.rows,
+                grid_dims.cols,
+                monitor_area,
+                monitor.reserved,
+                &label_options,
+            ),
+        };
+        let grid = grid.unwrap_or_else(|e| offline("building grid", e));
+
+        Session {
+            config,
+            config_path,
+            monitor,
+            monitor_override,
+            grid_dims,
+            label_options,
+            grid,
+        }
     }
 
-    println!();
-    println!("The Grid is online. End of line.");
+    /// Print the configuration report
+    fn report(&self) {
+        let Session {
+            config,
+            config_path,
+            monitor,
+            monitor_override,
+            grid_dims,
+            label_options,
+            grid,
+        } = self;
+        let (logical_width, logical_height) = monitor.logical_size();
+
+        // Display configuration report
+        println!("Configuration loaded successfully!");
+        match config_path {
+            Some(path) => println!("  File: {}", path.display()),
+            None => println!("  File: none found, using built-in defaults"),
+        }
+        println!();
+        println!("Active Monitor:");
+        println!("  Name:        {}", monitor.name);
+        println!("  Description: {}", monitor.description);
+        println!("  Resolution:  {}x{}", monitor.width, monitor.height);
+        println!("  Logical:     {}x{} (scale {}, transform {})",
+                 logical_width, logical_height, monitor.scale, monitor.transform);
+        println!("  Position:    ({}, {})", monitor.x, monitor.y);
+        println!("  Orientation: {}", grid_dims.orientation.as_str());
+        match monitor_override {
+            Some(key) => println!("  Overrides:   [monitor.\"{}\"]", key),
+            None => println!("  Overrides:   none (global settings)"),
+        }
+        println!();
+        println!("Grid Configuration:");
+        println!("  Base (landscape): {}x{} (rows x cols)", config.grid_rows, config.grid_cols);
+        println!("  Applied:          {}x{} (rows x cols)", grid_dims.rows, grid_dims.cols);
+        println!("  Total cells:      {}", grid_dims.total_cells());
+        println!("  Sub-grid:         {}x{} (rows x cols)", config.subgrid_rows, config.subgrid_cols);
+
+        if grid_dims.orientation.as_str() == "Portrait" {
+            println!();
+            println!("  Note: Grid dimensions auto-rotated for portrait orientation");
+        }
+
+        println!();
+        println!("Labels:");
+        println!("  Layout:   {}", label_options.layout.name);
+        println!("  Alphabet: {}", label_options.alphabet.letters().iter().collect::<String>());
+        println!("  Mode:     {:?}", label_options.mode);
+
+        println!();
+        println!("Border Settings:");
+        println!("  Enabled:  {}", config.border_enabled);
+        println!("  Color:    {}", config.border_color);
+        println!("  Width:    {} px", config.border_width);
+
+        println!();
+        println!("Reserved Areas:");
+        println!("  Reserved (l,t,r,b): {:?}", monitor.reserved);
+        println!("  Handling:           {:?}", config.reserved_area);
+
+        println!();
+        println!("Grid Calculations:");
+        println!("  Applied rows: {}", grid.rows);
+        println!("  Applied cols: {}", grid.cols);
+        println!("  Total cells:  {}", grid.total_cells());
+
+        // Show a sample of cell labels and their coordinates (see `hyprgrid dump` for all)
+        println!();
+        println!("Sample Cell Coordinates:");
+        for cell in grid.cells().take(3) {
+            let (cx, cy) = cell.coordinates.center();
+            println!("  Cell '{}': position ({}, {}), center at ({}, {})",
+                     cell.label, cell.coordinates.x, cell.coordinates.y, cx, cy);
+        }
+    }
 }
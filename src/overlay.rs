@@ -0,0 +1,1130 @@
+// Overlay module for HyprGrid
+//
+// This module shows the grid on top of the active monitor and feeds key presses
+// to the input state machine until a cell is selected or the user cancels.
+// The overlay is a wlr-layer-shell surface when the compositor supports it
+// (see `layer_shell.rs`) and a fullscreen iced window titled "HyprGrid"
+// otherwise. Both draw the same list of shapes built by `Overlay::scene`.
+
+use anyhow::{anyhow, Result};
+use iced::advanced::text::{self, Paragraph};
+use iced::keyboard::{self, key::Named};
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Stroke, Text};
+use iced::{
+    alignment, font, mouse, window, Color, Element, Fill, Font, Point, Rectangle, Renderer, Size,
+    Subscription, Task, Theme,
+};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{HyprGridConfig, LabelPosition, MonitorInfo};
+use crate::grid::{CellCoordinates, Grid, LabelMode, Ruler};
+use crate::input::{Action, InputHandler, InputOutcome, Key, Selection};
+use crate::layer_shell::LayerShell;
+
+/// Title of the overlay window (window rules match on it)
+pub const WINDOW_TITLE: &str = "HyprGrid";
+
+/// Smallest and largest label font size in pixels
+const LABEL_SIZE_RANGE: (f32, f32) = (8.0, 48.0);
+
+/// Width of a monospace glyph relative to the font size
+const GLYPH_ASPECT: f32 = 0.6;
+
+/// Line height of label text relative to the font size
+const LINE_HEIGHT: f32 = 1.2;
+
+/// Padding around label text inside its backdrop, relative to the font size
+const LABEL_PADDING: f32 = 0.25;
+
+/// Shade laid over cells that no longer match the typed prefix
+const DIMMED: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.5);
+
+/// Opacity kept by labels that no longer match the typed prefix
+const DIMMED_LABEL_OPACITY: f32 = 0.35;
+
+/// Outline width marking the last remaining candidate
+const READY_WIDTH: f32 = 3.0;
+
+/// A label font family
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontFamily {
+    Monospace,
+    SansSerif,
+    Serif,
+    /// A font installed on the system, by family name
+    Named(String),
+}
+
+impl FontFamily {
+    /// Resolve a `theme.font_family` setting
+    ///
+    /// The generic names are matched case-insensitively; anything else is
+    /// a family name.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "monospace" => FontFamily::Monospace,
+            "sans-serif" | "sans" => FontFamily::SansSerif,
+            "serif" => FontFamily::Serif,
+            _ => FontFamily::Named(name.trim().to_string()),
+        }
+    }
+
+}
+
+/// How the grid is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayStyle {
+    /// Whether to draw cell borders
+    pub border_enabled: bool,
+    /// Color of the cell borders
+    pub border_color: Color,
+    /// Width of the cell borders in pixels
+    pub border_width: f32,
+    /// Label font
+    pub font: FontFamily,
+    /// The label font as drawn by the iced window
+    pub iced_font: Font,
+    /// Fixed label font size (fitted to each cell when `None`)
+    pub font_size: Option<f32>,
+    /// Label text color
+    pub foreground: Color,
+    /// Backdrop behind each label
+    pub background: Color,
+    /// Typed part of matching labels and the last remaining candidate
+    pub highlight: Color,
+    /// Color laid over the whole screen
+    pub overlay_background: Color,
+    /// Where labels sit inside their cells
+    pub label_position: LabelPosition,
+}
+
+impl OverlayStyle {
+    /// Take the border and theme settings from the configuration
+    pub fn from_config(config: &HyprGridConfig) -> Self {
+        let theme = &config.theme;
+        let font = FontFamily::from_name(&theme.font_family);
+        let iced_font = match &font {
+            FontFamily::Monospace => Font::MONOSPACE,
+            FontFamily::SansSerif => Font::DEFAULT,
+            FontFamily::Serif => Font {
+                family: font::Family::Serif,
+                ..Font::DEFAULT
+            },
+            // iced only takes font names that live for the whole process, so
+            // the name is leaked here, once per style (one per overlay)
+            FontFamily::Named(name) => Font::with_name(Box::leak(name.clone().into_boxed_str())),
+        };
+
+        OverlayStyle {
+            border_enabled: config.border_enabled && config.border_width > 0,
+            border_color: config.border_color.into(),
+            border_width: config.border_width as f32,
+            font,
+            iced_font,
+            font_size: theme.font_size.map(|size| size as f32),
+            foreground: theme.foreground.into(),
+            background: theme.background.into(),
+            highlight: theme.highlight.into(),
+            overlay_background: theme.overlay_background.into(),
+            label_position: theme.label_position,
+        }
+    }
+}
+
+/// The outcome of a finished overlay session
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    /// The selected label and action
+    pub selection: Selection,
+    /// The selected cell, in global coordinates (where a drag ends)
+    pub area: CellCoordinates,
+    /// Where a drag starts, for [`Action::Drag`]
+    pub drag_from: Option<CellCoordinates>,
+}
+
+/// A drawing primitive, in window coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// A filled rectangle
+    Fill { rect: Rectangle, color: Color },
+    /// A rectangle outline, centered on the rectangle's edges
+    Outline {
+        rect: Rectangle,
+        color: Color,
+        width: f32,
+    },
+    /// Text centered in a rectangle
+    ///
+    /// The first characters can be drawn in another color, to show which
+    /// part of the label has already been typed.
+    Label {
+        text: String,
+        rect: Rectangle,
+        font: FontFamily,
+        size: f32,
+        color: Color,
+        highlight: Option<(usize, Color)>,
+    },
+}
+
+/// Messages handled by the overlay window
+#[derive(Debug, Clone)]
+enum Message {
+    /// A key was pressed
+    Key(Key),
+}
+
+/// The overlay state, shared by the layer-shell and window front ends
+pub struct Overlay {
+    /// The grid currently shown (replaced when a cell is refined)
+    grid: Grid,
+    /// The full grid, shown again to pick the end point of a drag
+    root: Grid,
+    /// The start point of a drag, once picked
+    drag_from: Option<CellCoordinates>,
+    /// The monitor covered by the overlay, in global coordinates
+    ///
+    /// This is also the window's area: with reserved areas excluded, the
+    /// grid covers only part of it.
+    screen: CellCoordinates,
+    /// Keyboard state machine
+    input: InputHandler,
+    /// How the grid is drawn
+    style: OverlayStyle,
+    /// Sub-grid size used when refining a cell
+    subgrid: (u32, u32),
+    /// A message shown until the next key, e.g. why a cell was not refined
+    notice: Option<String>,
+    /// Where the final selection is reported once the window closes
+    result: Arc<Mutex<Option<Target>>>,
+}
+
+impl Overlay {
+    fn new(
+        grid: Grid,
+        screen: CellCoordinates,
+        config: &HyprGridConfig,
+        result: Arc<Mutex<Option<Target>>>,
+    ) -> Self {
+        Overlay {
+            screen,
+            root: grid.clone(),
+            grid,
+            drag_from: None,
+            input: InputHandler::with_bindings(
+                config
+                    .key_bindings()
+                    .expect("key bindings are checked by HyprGridConfig::validate"),
+            ),
+            style: OverlayStyle::from_config(config),
+            subgrid: (config.subgrid_rows, config.subgrid_cols),
+            notice: None,
+            result,
+        }
+    }
+
+    /// Feed one key to the state machine
+    ///
+    /// A drag is picked in two steps: selecting a cell with [`Action::Drag`]
+    /// marks the start point and shows the full grid again, and the next
+    /// selection (with any confirm key) is the end point. Either can be
+    /// refined with TAB first.
+    ///
+    /// # Returns
+    /// `true` if the overlay is done and should close
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        self.notice = None;
+        match self.input.handle_key(key, &self.grid) {
+            InputOutcome::Selected(mut selection) => {
+                let Some(cell) = self.grid.get_cell(&selection.label) else {
+                    return true;
+                };
+                let area = cell.coordinates.clone();
+
+                if selection.action == Action::Drag && self.drag_from.is_none() {
+                    self.drag_from = Some(area);
+                    self.grid = self.root.clone();
+                    self.input.reset();
+                    return false;
+                }
+                if self.drag_from.is_some() {
+                    selection.action = Action::Drag;
+                }
+
+                *self.result.lock().unwrap() = Some(Target {
+                    area,
+                    selection,
+                    drag_from: self.drag_from.take(),
+                });
+                true
+            }
+            InputOutcome::Cancelled => true,
+            InputOutcome::Refine(label) => {
+                let (rows, cols) = self.subgrid;
+                // On failure the label stays typed, so it can still be confirmed
+                match self.grid.subdivide(&label, rows, cols) {
+                    Ok(subgrid) => {
+                        self.grid = subgrid;
+                        self.input.reset();
+                    }
+                    Err(e) => self.notice = Some(e.to_string()),
+                }
+                false
+            }
+            InputOutcome::Updated | InputOutcome::Ignored | InputOutcome::Rejected => false,
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Key(key) => {
+                if self.handle_key(key) {
+                    iced::exit()
+                } else {
+                    Task::none()
+                }
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        Canvas::new(self).width(Fill).height(Fill).into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        keyboard::on_key_press(|key, _modifiers| Some(Message::Key(to_input_key(&key))))
+    }
+
+    /// Convert a global rectangle to window coordinates
+    fn local(&self, area: &CellCoordinates) -> Rectangle {
+        Rectangle {
+            x: (area.x - self.screen.x) as f32,
+            y: (area.y - self.screen.y) as f32,
+            width: area.width as f32,
+            height: area.height as f32,
+        }
+    }
+
+    /// Build the shapes to draw for a window of the given size
+    ///
+    /// Once something is typed, cells whose label does not start with it are
+    /// dimmed and the typed part of the other labels is highlighted. When a
+    /// single candidate is left it is outlined, as it can be confirmed.
+    pub fn scene(&self, size: Size) -> Vec<Shape> {
+        let typed = self.input.buffer();
+        let mut shapes = vec![Shape::Fill {
+            rect: Rectangle::new(Point::ORIGIN, size),
+            color: self.style.overlay_background,
+        }];
+
+        if !typed.is_empty() {
+            shapes.extend(
+                self.grid
+                    .cells()
+                    .filter(|cell| !cell.label.starts_with(typed))
+                    .map(|cell| Shape::Fill {
+                        rect: self.local(&cell.coordinates),
+                        color: DIMMED,
+                    }),
+            );
+        }
+
+        if self.style.border_enabled {
+            shapes.extend(self.grid.cells().map(|cell| Shape::Outline {
+                rect: self.local(&cell.coordinates),
+                color: self.style.border_color,
+                width: self.style.border_width,
+            }));
+        }
+
+        // In regional mode the first letter picks a block: outline it
+        if self.grid.label_mode() == LabelMode::Regional && typed.len() == 1 {
+            if let Some(block) = self.grid.prefix_bounds(typed) {
+                shapes.push(Shape::Outline {
+                    rect: self.local(&block),
+                    color: self.style.highlight,
+                    width: READY_WIDTH,
+                });
+            }
+        }
+
+        match self.grid.rulers() {
+            Some((rows, columns)) => self.ruler_labels(&mut shapes, &rows, &columns),
+            None => {
+                for cell in self.grid.cells() {
+                    let area = self.local(&cell.coordinates);
+                    self.label(&mut shapes, &cell.label, area, typed, self.style.label_position);
+                }
+            }
+        }
+
+        if let Some(start) = &self.drag_from {
+            shapes.push(Shape::Outline {
+                rect: self.local(start),
+                color: self.style.highlight,
+                width: READY_WIDTH,
+            });
+        }
+
+        if let Some(cell) = self.input.candidate(&self.grid) {
+            shapes.push(Shape::Outline {
+                rect: self.local(&cell.coordinates),
+                color: self.style.highlight,
+                width: READY_WIDTH,
+            });
+        }
+
+        if let Some(notice) = &self.notice {
+            self.label(&mut shapes, notice, Rectangle::new(Point::ORIGIN, size), "", LabelPosition::Center);
+        }
+
+        shapes
+    }
+
+    /// Add coordinate-mode ruler letters along the left and top edges
+    ///
+    /// The first typed letter filters the row ruler and the second one the
+    /// column ruler.
+    fn ruler_labels(&self, shapes: &mut Vec<Shape>, rows: &[Ruler], columns: &[Ruler]) {
+        let typed = self.input.buffer();
+        let typed_row = typed.get(0..1).unwrap_or("");
+        let typed_column = typed.get(1..2).unwrap_or("");
+        let area = self.local(&self.grid.area);
+        let thickness = |rulers: &[Ruler]| {
+            let shortest = rulers.iter().map(|r| r.length).min().unwrap_or(0) as f32;
+            shortest.clamp(LABEL_SIZE_RANGE.0, LABEL_SIZE_RANGE.1 * 1.5)
+        };
+
+        let width = thickness(rows);
+        for ruler in rows {
+            let y = (ruler.start - self.screen.y) as f32;
+            let rect = Rectangle::new(Point::new(area.x, y), Size::new(width, ruler.length as f32));
+            self.label(shapes, &ruler.letter.to_string(), rect, typed_row, LabelPosition::Center);
+        }
+
+        let height = thickness(columns);
+        for ruler in columns {
+            let x = (ruler.start - self.screen.x) as f32;
+            let rect = Rectangle::new(Point::new(x, area.y), Size::new(ruler.length as f32, height));
+            self.label(shapes, &ruler.letter.to_string(), rect, typed_column, LabelPosition::Center);
+        }
+    }
+
+    /// Add a label and its backdrop, placed in `area` and styled against the
+    /// typed prefix
+    ///
+    /// With nothing typed the label is drawn plainly; otherwise the typed
+    /// part of a matching label is highlighted and a label that does not
+    /// match is faded.
+    fn label(
+        &self,
+        shapes: &mut Vec<Shape>,
+        text: &str,
+        area: Rectangle,
+        typed: &str,
+        position: LabelPosition,
+    ) {
+        let style = &self.style;
+        let (opacity, highlight) = if typed.is_empty() {
+            (1.0, None)
+        } else if text.starts_with(typed) {
+            (1.0, Some((typed.chars().count(), style.highlight)))
+        } else {
+            (DIMMED_LABEL_OPACITY, None)
+        };
+
+        let chars = text.chars().count();
+        let size = style.font_size.unwrap_or_else(|| match position {
+            LabelPosition::Center => label_size(area.width, area.height, chars),
+            // Keep corner labels small enough to leave the center visible
+            LabelPosition::Corner => label_size(area.width, area.height * 0.5, chars),
+        });
+        let rect = label_box(area, size, chars, position);
+
+        let background = fade(style.background, opacity);
+        if background.a > 0.0 {
+            shapes.push(Shape::Fill { rect, color: background });
+        }
+        shapes.push(Shape::Label {
+            text: text.to_string(),
+            rect,
+            font: style.font.clone(),
+            size,
+            color: fade(style.foreground, opacity),
+            highlight,
+        });
+    }
+}
+
+impl canvas::Program<Message> for Overlay {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        for shape in self.scene(bounds.size()) {
+            match shape {
+                Shape::Fill { rect, color } => {
+                    frame.fill_rectangle(rect.position(), rect.size(), color)
+                }
+                Shape::Outline { rect, color, width } => frame.stroke_rectangle(
+                    rect.position(),
+                    rect.size(),
+                    Stroke::default().with_color(color).with_width(width),
+                ),
+                // Every label uses the style's font, already resolved for iced
+                Shape::Label {
+                    text,
+                    rect,
+                    font: _,
+                    size,
+                    color,
+                    highlight,
+                } => {
+                    let font = self.style.iced_font;
+                    let span = |content: String, position: Point, color: Color, horizontal| Text {
+                        content,
+                        position,
+                        color,
+                        size: size.into(),
+                        font,
+                        horizontal_alignment: horizontal,
+                        vertical_alignment: alignment::Vertical::Center,
+                        ..Text::default()
+                    };
+                    let Some((count, highlight_color)) = highlight else {
+                        frame.fill_text(span(text, rect.center(), color, alignment::Horizontal::Center));
+                        continue;
+                    };
+
+                    // The typed part and the rest are two spans: the rest starts
+                    // where the measured typed part ends
+                    let typed: String = text.chars().take(count).collect();
+                    let rest: String = text.chars().skip(count).collect();
+                    let typed_width = text_width(&typed, size, font);
+                    let width = typed_width + text_width(&rest, size, font);
+                    let start = Point::new(rect.center_x() - width / 2.0, rect.center_y());
+                    let after = Point::new(start.x + typed_width, start.y);
+                    frame.fill_text(span(typed, start, highlight_color, alignment::Horizontal::Left));
+                    frame.fill_text(span(rest, after, color, alignment::Horizontal::Left));
+                }
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Measure the width of a line of text as the renderer lays it out
+///
+/// This matches the defaults of the canvas [`Text`] the labels are drawn with.
+fn text_width(content: &str, size: f32, font: Font) -> f32 {
+    let canvas_text = Text::default();
+    <Renderer as text::Renderer>::Paragraph::with_text(text::Text {
+        content,
+        bounds: Size::INFINITY,
+        size: size.into(),
+        line_height: canvas_text.line_height,
+        font,
+        horizontal_alignment: alignment::Horizontal::Left,
+        vertical_alignment: alignment::Vertical::Top,
+        shaping: canvas_text.shaping,
+        wrapping: text::Wrapping::None,
+    })
+    .min_width()
+}
+
+/// The backdrop rectangle of a label, which also frames its text
+///
+/// The text box is estimated from the font size, as renderers only measure
+/// text when drawing it.
+fn label_box(area: Rectangle, size: f32, chars: usize, position: LabelPosition) -> Rectangle {
+    let padding = size * LABEL_PADDING;
+    let width = chars as f32 * size * GLYPH_ASPECT + 2.0 * padding;
+    let height = size * LINE_HEIGHT + 2.0 * padding;
+    let origin = match position {
+        LabelPosition::Center => Point::new(
+            area.center_x() - width / 2.0,
+            area.center_y() - height / 2.0,
+        ),
+        LabelPosition::Corner => Point::new(area.x + padding, area.y + padding),
+    };
+    Rectangle::new(origin, Size::new(width, height))
+}
+
+/// Scale a color's opacity
+fn fade(color: Color, opacity: f32) -> Color {
+    Color {
+        a: color.a * opacity,
+        ..color
+    }
+}
+
+/// Translate an iced key into the input state machine's key type
+fn to_input_key(key: &keyboard::Key) -> Key {
+    match key {
+        keyboard::Key::Character(text) => match text.chars().next() {
+            Some(c) => Key::Char(c),
+            None => Key::Other,
+        },
+        keyboard::Key::Named(Named::Space) => Key::Space,
+        keyboard::Key::Named(Named::Enter) => Key::Enter,
+        keyboard::Key::Named(Named::Backspace) => Key::Backspace,
+        keyboard::Key::Named(Named::Escape) => Key::Escape,
+        keyboard::Key::Named(Named::Tab) => Key::Tab,
+        _ => Key::Other,
+    }
+}
+
+/// Pick a font size so a label of `chars` characters fits its cell
+///
+/// The label takes at most half the cell height and 80% of its width, within
+/// [`LABEL_SIZE_RANGE`].
+fn label_size(width: f32, height: f32, chars: usize) -> f32 {
+    let by_height = height * 0.5;
+    let by_width = width * 0.8 / (chars.max(1) as f32 * GLYPH_ASPECT);
+    by_height
+        .min(by_width)
+        .clamp(LABEL_SIZE_RANGE.0, LABEL_SIZE_RANGE.1)
+}
+
+/// Show the overlay for a grid and wait until the user is done
+///
+/// The grid is shown on a layer-shell surface on the overlay layer of the
+/// named output, with exclusive keyboard focus, so no window rules are
+/// needed. If the compositor lacks layer-shell support or does not announce
+/// the output by name (or `layer_shell` is disabled in the configuration), a
+/// borderless, transparent, fullscreen window titled "HyprGrid" is opened
+/// instead. Either closes after a selection or on ESC.
+///
+/// A window cannot be placed on a given output: it always opens on the
+/// focused monitor, so the fallback fails for any other monitor.
+///
+/// # Arguments
+/// * `grid` - The grid to display, in global coordinates
+/// * `screen` - The logical area of the monitor, in global coordinates,
+///   which the overlay covers (the grid may cover less of it)
+/// * `config` - Border, sub-grid and overlay settings
+/// * `output` - The monitor to cover
+/// * `action` - Action performed by every confirm key, if fixed (see
+///   [`InputHandler::set_action`])
+///
+/// # Returns
+/// - `Ok(Some(Target))` if a cell and action were selected
+/// - `Ok(None)` if the user cancelled
+/// - `Err` if the overlay could not be shown, or only as a window on another
+///   monitor than `output`
+pub fn run(
+    grid: Grid,
+    screen: CellCoordinates,
+    config: &HyprGridConfig,
+    output: &MonitorInfo,
+    action: Option<Action>,
+) -> Result<Option<Target>> {
+    let result = Arc::new(Mutex::new(None));
+    let mut overlay = Overlay::new(grid, screen, config, Arc::clone(&result));
+    if let Some(action) = action {
+        overlay.input.set_action(action);
+    }
+
+    let layer_shell = if config.layer_shell {
+        match LayerShell::connect(&output.name) {
+            Ok(shell) => Some(shell),
+            Err(e) => {
+                eprintln!("Layer shell unavailable, falling back to a window: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    match layer_shell {
+        Some(shell) => shell.run(overlay)?,
+        None if !output.focused => {
+            return Err(anyhow!(
+                "Cannot show the overlay on {}: without the layer shell it is a window,\n\
+                 which always opens on the focused monitor. Focus {} first, or enable\n\
+                 layer_shell in the configuration if your compositor supports it.",
+                output.name,
+                output.name
+            ));
+        }
+        None => run_window(overlay)?,
+    }
+
+    let target = result.lock().unwrap().take();
+    Ok(target)
+}
+
+/// Show the overlay in a regular fullscreen window
+fn run_window(overlay: Overlay) -> Result<()> {
+    let area = &overlay.screen;
+    let settings = window::Settings {
+        size: Size::new(area.width as f32, area.height as f32),
+        decorations: false,
+        transparent: true,
+        resizable: false,
+        level: window::Level::AlwaysOnTop,
+        platform_specific: window::settings::PlatformSpecific {
+            application_id: "hyprgrid".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let text_color = overlay.style.foreground;
+    iced::application(WINDOW_TITLE, Overlay::update, Overlay::view)
+        .subscription(Overlay::subscription)
+        .style(move |_, _| iced::application::Appearance {
+            background_color: Color::TRANSPARENT,
+            text_color,
+        })
+        .window(settings)
+        .run_with(move || {
+            let startup = window::get_oldest().and_then(|id| {
+                Task::batch([
+                    window::change_mode(id, window::Mode::Fullscreen),
+                    window::gain_focus(id),
+                ])
+            });
+            (overlay, startup)
+        })
+        .map_err(|e| anyhow!("Failed to open the overlay window: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::LabelOptions;
+
+    fn config() -> HyprGridConfig {
+        toml::from_str("grid_rows = 2\ngrid_cols = 3\nsubgrid_rows = 2\nsubgrid_cols = 2").unwrap()
+    }
+
+    fn overlay() -> (Overlay, Arc<Mutex<Option<Target>>>) {
+        let result = Arc::new(Mutex::new(None));
+        let area = CellCoordinates { x: 1920, y: 0, width: 600, height: 400 };
+        let grid = Grid::for_area(2, 3, area.clone(), &Default::default()).unwrap();
+        (Overlay::new(grid, area, &config(), Arc::clone(&result)), result)
+    }
+
+    #[test]
+    fn test_window_fallback_needs_focused_monitor() {
+        let config: HyprGridConfig = toml::from_str("grid_rows = 2\ngrid_cols = 3\nlayer_shell = false").unwrap();
+        let monitor: MonitorInfo = serde_json::from_str(
+            r#"{"name": "DP-2", "width": 600, "height": 400, "x": 1920, "y": 0, "scale": 1.0, "focused": false}"#,
+        )
+        .unwrap();
+        let screen = monitor.logical_area();
+        let grid = Grid::for_area(2, 3, screen.clone(), &Default::default()).unwrap();
+
+        let err = run(grid, screen, &config, &monitor, None).unwrap_err();
+        assert!(err.to_string().contains("Cannot show the overlay on DP-2"));
+    }
+
+    #[test]
+    fn test_key_translation() {
+        assert_eq!(to_input_key(&keyboard::Key::Character("a".into())), Key::Char('a'));
+        assert_eq!(to_input_key(&keyboard::Key::Named(Named::Space)), Key::Space);
+        assert_eq!(to_input_key(&keyboard::Key::Named(Named::Enter)), Key::Enter);
+        assert_eq!(to_input_key(&keyboard::Key::Named(Named::Escape)), Key::Escape);
+        assert_eq!(to_input_key(&keyboard::Key::Named(Named::Tab)), Key::Tab);
+        assert_eq!(to_input_key(&keyboard::Key::Named(Named::F1)), Key::Other);
+    }
+
+    #[test]
+    fn test_selection_closes_overlay() {
+        let (mut overlay, result) = overlay();
+
+        assert!(!overlay.handle_key(Key::Char('a')));
+        assert!(!overlay.handle_key(Key::Char('s')));
+        assert!(overlay.handle_key(Key::Enter));
+
+        let target = result.lock().unwrap().clone().unwrap();
+        assert_eq!(target.selection.action, Action::RightClick);
+        assert_eq!(target.area, CellCoordinates { x: 2120, y: 0, width: 200, height: 200 });
+    }
+
+    #[test]
+    fn test_escape_closes_without_selection() {
+        let (mut overlay, result) = overlay();
+        assert!(overlay.handle_key(Key::Escape));
+        assert!(result.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_refine_replaces_grid() {
+        let (mut overlay, result) = overlay();
+
+        overlay.handle_key(Key::Char('a'));
+        overlay.handle_key(Key::Char('a'));
+        assert!(!overlay.handle_key(Key::Tab));
+        assert_eq!(overlay.grid.total_cells(), 4);
+        assert_eq!(overlay.input.buffer(), "");
+
+        // Window coordinates stay relative to the monitor, not the sub-grid
+        let cell = overlay.grid.get_cell("as").unwrap();
+        assert_eq!(overlay.local(&cell.coordinates).x, 100.0);
+
+        overlay.handle_key(Key::Char('a'));
+        overlay.handle_key(Key::Char('s'));
+        assert!(overlay.handle_key(Key::Space));
+        let target = result.lock().unwrap().clone().unwrap();
+        assert_eq!(target.area, CellCoordinates { x: 2020, y: 0, width: 100, height: 100 });
+    }
+
+    #[test]
+    fn test_refine_failure_shows_notice() {
+        let result = Arc::new(Mutex::new(None));
+        let area = CellCoordinates { x: 0, y: 0, width: 3, height: 2 };
+        let grid = Grid::for_area(2, 3, area.clone(), &Default::default()).unwrap();
+        let mut overlay = Overlay::new(grid, area, &config(), result);
+        let notice = |overlay: &Overlay| {
+            overlay.scene(Size::new(3.0, 2.0)).into_iter().find_map(|shape| match shape {
+                Shape::Label { text, .. } if text.contains("single pixel") => Some(text),
+                _ => None,
+            })
+        };
+
+        // A one-pixel cell cannot be refined: the grid stays and says why
+        overlay.handle_key(Key::Char('a'));
+        overlay.handle_key(Key::Char('a'));
+        assert!(!overlay.handle_key(Key::Tab));
+        assert_eq!(overlay.grid.total_cells(), 6);
+        assert_eq!(
+            notice(&overlay).as_deref(),
+            Some("Cell 'aa' is a single pixel and cannot be refined")
+        );
+
+        // The label is still typed, and the next key clears the notice
+        assert!(overlay.handle_key(Key::Space));
+        assert_eq!(notice(&overlay), None);
+    }
+
+    #[test]
+    fn test_drag_picks_two_points() {
+        use crate::pointer::execute_drag;
+        use crate::pointer::recording::{PointerEvent, RecordingBackend};
+        use crate::pointer::Button;
+
+        let (mut overlay, result) = overlay();
+
+        // Start point: refine "aa", then drag from its sub-cell "as"
+        for key in [Key::Char('a'), Key::Char('a'), Key::Tab, Key::Char('a'), Key::Char('s')] {
+            assert!(!overlay.handle_key(key));
+        }
+        assert!(!overlay.handle_key(Key::Char('/')));
+        assert!(result.lock().unwrap().is_none());
+        assert_eq!(overlay.grid.total_cells(), 6);
+        assert_eq!(overlay.input.buffer(), "");
+
+        // The start point stays marked while the end point is picked
+        let scene = overlay.scene(Size::new(600.0, 400.0));
+        assert!(scene.contains(&Shape::Outline {
+            rect: Rectangle::new(Point::new(100.0, 0.0), Size::new(100.0, 100.0)),
+            color: overlay.style.highlight,
+            width: READY_WIDTH,
+        }));
+
+        // End point: refine "af" and confirm with any key
+        for key in [Key::Char('a'), Key::Char('f'), Key::Tab, Key::Char('a'), Key::Char('d')] {
+            assert!(!overlay.handle_key(key));
+        }
+        assert!(overlay.handle_key(Key::Space));
+
+        let target = result.lock().unwrap().clone().unwrap();
+        assert_eq!(target.selection.action, Action::Drag);
+        let from = target.drag_from.unwrap();
+        assert_eq!(from, CellCoordinates { x: 2020, y: 0, width: 100, height: 100 });
+        assert_eq!(target.area, CellCoordinates { x: 1920, y: 300, width: 100, height: 100 });
+
+        let mut backend = RecordingBackend::default();
+        execute_drag(&mut backend, &from, &target.area).unwrap();
+        assert_eq!(
+            backend.events,
+            vec![
+                PointerEvent::MoveTo(2070, 50),
+                PointerEvent::Press(Button::Left),
+                PointerEvent::MoveTo(1970, 350),
+                PointerEvent::Release(Button::Left),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_excluded_strip_keeps_monitor_origin() {
+        // A 38 px bar at the top is left uncovered by the grid, but the
+        // overlay still covers the whole monitor
+        let result = Arc::new(Mutex::new(None));
+        let screen = CellCoordinates { x: 1920, y: 0, width: 600, height: 438 };
+        let grid = Grid::for_area(2, 3, screen.inset([0, 38, 0, 0]), &Default::default()).unwrap();
+        let mut overlay = Overlay::new(grid, screen, &config(), Arc::clone(&result));
+
+        let scene = overlay.scene(Size::new(600.0, 438.0));
+        assert_eq!(
+            scene[1],
+            Shape::Outline {
+                rect: Rectangle::new(Point::new(0.0, 38.0), Size::new(200.0, 200.0)),
+                color: Color::WHITE,
+                width: 1.0,
+            }
+        );
+
+        // The cell drawn at y = 38 in the window is the one clicked at y = 38
+        overlay.handle_key(Key::Char('a'));
+        overlay.handle_key(Key::Char('a'));
+        assert!(overlay.handle_key(Key::Space));
+        let target = result.lock().unwrap().clone().unwrap();
+        assert_eq!(target.area, CellCoordinates { x: 1920, y: 38, width: 200, height: 200 });
+    }
+
+    #[test]
+    fn test_scene() {
+        let (overlay, _) = overlay();
+        let scene = overlay.scene(Size::new(600.0, 400.0));
+
+        // Background, 6 borders, 6 labels with their backdrops
+        assert_eq!(scene.len(), 19);
+        assert_eq!(
+            scene[0],
+            Shape::Fill {
+                rect: Rectangle::new(Point::ORIGIN, Size::new(600.0, 400.0)),
+                color: Color::from_rgba8(0, 0, 0, 0x4D as f32 / 255.0),
+            }
+        );
+        assert_eq!(
+            scene[2],
+            Shape::Outline {
+                rect: Rectangle::new(Point::new(200.0, 0.0), Size::new(200.0, 200.0)),
+                color: Color::WHITE,
+                width: 1.0,
+            }
+        );
+        assert!(matches!(scene[17], Shape::Fill { rect, .. } if rect.center() == Point::new(500.0, 300.0)));
+        match &scene[18] {
+            Shape::Label { text, rect, font, .. } => {
+                assert_eq!(text, "ah");
+                assert_eq!(rect.center(), Point::new(500.0, 300.0));
+                assert_eq!(*font, FontFamily::Monospace);
+            }
+            other => panic!("expected a label, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scene_filters_typed_prefix() {
+        type Styled = (String, Color, Option<(usize, Color)>);
+        let (mut overlay, _) = overlay();
+        let (white, yellow) = (Color::WHITE, overlay.style.highlight);
+        let labels = |scene: &[Shape]| -> Vec<Styled> {
+            scene
+                .iter()
+                .filter_map(|shape| match shape {
+                    Shape::Label { text, color, highlight, .. } => Some((text.clone(), *color, *highlight)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Every label starts with "a": nothing dimmed, no single candidate yet
+        overlay.handle_key(Key::Char('a'));
+        let scene = overlay.scene(Size::new(600.0, 400.0));
+        assert_eq!(scene.len(), 19);
+        assert!(labels(&scene)
+            .iter()
+            .all(|(_, color, highlight)| *color == white && *highlight == Some((1, yellow))));
+
+        // "ad" leaves one candidate: the other five cells are dimmed and it is
+        // outlined as ready to confirm
+        overlay.handle_key(Key::Char('d'));
+        let scene = overlay.scene(Size::new(600.0, 400.0));
+        assert_eq!(scene.len(), 25);
+        let dimmed = scene
+            .iter()
+            .filter(|shape| matches!(shape, Shape::Fill { color, .. } if *color == DIMMED))
+            .count();
+        assert_eq!(dimmed, 5);
+
+        let labels = labels(&scene);
+        assert_eq!(labels[0], ("aa".to_string(), fade(white, DIMMED_LABEL_OPACITY), None));
+        assert_eq!(labels[2], ("ad".to_string(), white, Some((2, yellow))));
+        assert_eq!(
+            scene[24],
+            Shape::Outline {
+                rect: Rectangle::new(Point::new(400.0, 0.0), Size::new(200.0, 200.0)),
+                color: yellow,
+                width: READY_WIDTH,
+            }
+        );
+    }
+
+    #[test]
+    fn test_scene_outlines_regional_block() {
+        let result = Arc::new(Mutex::new(None));
+        let options = LabelOptions {
+            mode: LabelMode::Regional,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 1920, y: 0, width: 2000, height: 1000 };
+        let grid = Grid::for_area(10, 20, area.clone(), &options).unwrap();
+        let mut overlay = Overlay::new(grid, area, &config(), result);
+        let outlines = |overlay: &Overlay| -> Vec<Rectangle> {
+            overlay
+                .scene(Size::new(2000.0, 1000.0))
+                .into_iter()
+                .filter_map(|shape| match shape {
+                    Shape::Outline { rect, width, .. } if width == READY_WIDTH => Some(rect),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert!(outlines(&overlay).is_empty());
+
+        // The first letter outlines its 4×4 block of cells
+        overlay.handle_key(Key::Char('a'));
+        assert_eq!(
+            outlines(&overlay),
+            vec![Rectangle::new(Point::ORIGIN, Size::new(400.0, 400.0))]
+        );
+
+        // The second letter leaves only the candidate outlined
+        overlay.handle_key(Key::Char('s'));
+        assert_eq!(
+            outlines(&overlay),
+            vec![Rectangle::new(Point::new(100.0, 0.0), Size::new(100.0, 100.0))]
+        );
+    }
+
+    #[test]
+    fn test_scene_coordinate_rulers() {
+        let result = Arc::new(Mutex::new(None));
+        let options = LabelOptions {
+            mode: LabelMode::Coordinates,
+            ..LabelOptions::default()
+        };
+        let area = CellCoordinates { x: 0, y: 0, width: 600, height: 400 };
+        let grid = Grid::for_area(2, 3, area.clone(), &options).unwrap();
+        let overlay = Overlay::new(grid, area, &config(), result);
+
+        // One letter per row and per column instead of one label per cell
+        let labels: Vec<String> = overlay
+            .scene(Size::new(600.0, 400.0))
+            .into_iter()
+            .filter_map(|shape| match shape {
+                Shape::Label { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(labels, vec!["a", "s", "a", "s", "d"]);
+    }
+
+    #[test]
+    fn test_text_width() {
+        // The typed span ends where the rest begins
+        let font = Font::MONOSPACE;
+        let (typed, rest) = (text_width("a", 20.0, font), text_width("sd", 20.0, font));
+        assert!(typed > 0.0);
+        assert!((typed + rest - text_width("asd", 20.0, font)).abs() < 0.5);
+        assert!(text_width("asd", 40.0, font) > text_width("asd", 20.0, font));
+    }
+
+    #[test]
+    fn test_label_size() {
+        // Tall narrow cells are limited by width, wide flat ones by height
+        assert_eq!(label_size(96.0, 108.0, 2), 48.0);
+        assert!((label_size(60.0, 200.0, 2) - 40.0).abs() < 0.01);
+        assert_eq!(label_size(200.0, 40.0, 2), 20.0);
+        // Never unreadably small
+        assert_eq!(label_size(5.0, 5.0, 3), LABEL_SIZE_RANGE.0);
+    }
+
+    #[test]
+    fn test_style_from_config() {
+        let style = OverlayStyle::from_config(&config());
+        assert!(style.border_enabled);
+        assert_eq!(style.border_color, Color::WHITE);
+        assert_eq!(style.border_width, 1.0);
+
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 2\ngrid_cols = 3\nborder_color = \"#FF0000\"\nborder_width = 0",
+        )
+        .unwrap();
+        let style = OverlayStyle::from_config(&config);
+        assert!(!style.border_enabled);
+        assert_eq!(style.border_color, Color::from_rgb(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_style_from_theme() {
+        let style = OverlayStyle::from_config(&config());
+        assert_eq!(style.font, FontFamily::Monospace);
+        assert_eq!(style.iced_font, Font::MONOSPACE);
+        assert_eq!(style.font_size, None);
+        assert_eq!(style.foreground, Color::WHITE);
+        assert_eq!(style.highlight, Color::from_rgb8(0xFF, 0xCC, 0x00));
+        assert_eq!(style.label_position, LabelPosition::Center);
+
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 2\ngrid_cols = 3\n[theme]\nfont_family = \"Fira Code\"\nfont_size = 20\n\
+             foreground = \"#000\"\nbackground = \"#FFFFFF80\"\nlabel_position = \"corner\"",
+        )
+        .unwrap();
+        let style = OverlayStyle::from_config(&config);
+        assert_eq!(style.font, FontFamily::Named("Fira Code".to_string()));
+        assert_eq!(style.iced_font, Font::with_name("Fira Code"));
+
+        // A second style keeps its own font name
+        let config: HyprGridConfig =
+            toml::from_str("grid_rows = 2\ngrid_cols = 3\n[theme]\nfont_family = \"Iosevka\"").unwrap();
+        assert_eq!(OverlayStyle::from_config(&config).iced_font, Font::with_name("Iosevka"));
+        assert_eq!(style.font_size, Some(20.0));
+        assert_eq!(style.foreground, Color::BLACK);
+        assert_eq!(style.background, Color::from_rgba8(255, 255, 255, 0x80 as f32 / 255.0));
+        assert_eq!(FontFamily::from_name("Sans-Serif"), FontFamily::SansSerif);
+    }
+
+    #[test]
+    fn test_label_placement() {
+        let area = Rectangle::new(Point::new(100.0, 50.0), Size::new(200.0, 100.0));
+
+        let centered = label_box(area, 20.0, 2, LabelPosition::Center);
+        assert_eq!(centered.center(), area.center());
+        // Two glyphs of 12px plus 5px padding on each side
+        assert_eq!(centered.width, 34.0);
+
+        let corner = label_box(area, 20.0, 2, LabelPosition::Corner);
+        assert_eq!(corner.position(), Point::new(105.0, 55.0));
+    }
+
+    #[test]
+    fn test_corner_labels_with_fixed_size() {
+        let result = Arc::new(Mutex::new(None));
+        let config: HyprGridConfig = toml::from_str(
+            "grid_rows = 2\ngrid_cols = 3\n[theme]\nfont_size = 10\nbackground = \"#0000\"\nlabel_position = \"corner\"",
+        )
+        .unwrap();
+        let area = CellCoordinates { x: 0, y: 0, width: 600, height: 400 };
+        let grid = Grid::for_area(2, 3, area.clone(), &Default::default()).unwrap();
+        let overlay = Overlay::new(grid, area, &config, result);
+
+        // A transparent label background adds no backdrops
+        let scene = overlay.scene(Size::new(600.0, 400.0));
+        assert_eq!(scene.len(), 13);
+        match &scene[7] {
+            Shape::Label { text, rect, size, .. } => {
+                assert_eq!(text, "aa");
+                assert_eq!(*size, 10.0);
+                assert_eq!(rect.position(), Point::new(2.5, 2.5));
+            }
+            other => panic!("expected a label, got {:?}", other),
+        }
+    }
+}
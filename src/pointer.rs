@@ -0,0 +1,570 @@
+// Pointer action module for HyprGrid
+//
+// This module turns a selection into synthesized pointer input. The actual
+// input injection is hidden behind the `PointerBackend` trait so the execution
+// logic can be tested with a recording fake instead of moving the real cursor.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+
+use crate::grid::{CellCoordinates, Grid};
+use crate::input::Action;
+
+/// A mouse button that can be clicked, pressed or released
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Right,
+    Middle,
+}
+
+impl Button {
+    /// The button identifier used by `ydotool click`
+    fn ydotool_code(&self) -> u8 {
+        match self {
+            Button::Left => 0x00,
+            Button::Right => 0x01,
+            Button::Middle => 0x02,
+        }
+    }
+}
+
+/// A modifier key held down during a click
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Shift,
+    Ctrl,
+    Alt,
+}
+
+impl Modifier {
+    /// The Linux input event code of the left-hand key, used by `ydotool key`
+    fn keycode(&self) -> u16 {
+        match self {
+            Modifier::Shift => 42,
+            Modifier::Ctrl => 29,
+            Modifier::Alt => 56,
+        }
+    }
+}
+
+/// Something that can synthesize pointer input
+///
+/// Coordinates are absolute screen positions in pixels.
+pub trait PointerBackend {
+    /// Move the cursor to an absolute position
+    fn move_to(&mut self, x: i32, y: i32) -> Result<()>;
+    /// Press and release a button
+    fn click(&mut self, button: Button) -> Result<()>;
+    /// Press a button without releasing it
+    fn press(&mut self, button: Button) -> Result<()>;
+    /// Release a previously pressed button
+    fn release(&mut self, button: Button) -> Result<()>;
+    /// Scroll the wheel by the given number of steps (positive is down/right)
+    #[allow(dead_code)]
+    fn scroll(&mut self, dx: i32, dy: i32) -> Result<()>;
+    /// Press a modifier key without releasing it
+    fn key_down(&mut self, modifier: Modifier) -> Result<()>;
+    /// Release a previously pressed modifier key
+    fn key_up(&mut self, modifier: Modifier) -> Result<()>;
+}
+
+/// Pointer backend that shells out to `ydotool`
+///
+/// Requires the `ydotoold` daemon to be running. Commands are executed
+/// directly with an argument array (no shell), one process per step.
+#[derive(Debug, Clone)]
+pub struct YdotoolBackend {
+    /// Name or path of the ydotool executable
+    program: String,
+}
+
+impl Default for YdotoolBackend {
+    fn default() -> Self {
+        YdotoolBackend {
+            program: "ydotool".to_string(),
+        }
+    }
+}
+
+impl YdotoolBackend {
+    /// Create a backend using the `ydotool` found in `PATH`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arguments for an absolute cursor move
+    fn move_args(x: i32, y: i32) -> Vec<String> {
+        vec![
+            "mousemove".to_string(),
+            "--absolute".to_string(),
+            "-x".to_string(),
+            x.to_string(),
+            "-y".to_string(),
+            y.to_string(),
+        ]
+    }
+
+    /// Arguments for a button event
+    ///
+    /// ydotool encodes the event in the high bits of the button code:
+    /// `0x40` is button down, `0x80` is button up, `0xC0` is a full click.
+    fn button_args(button: Button, flags: u8) -> Vec<String> {
+        vec![
+            "click".to_string(),
+            format!("0x{:02X}", flags | button.ydotool_code()),
+        ]
+    }
+
+    /// Arguments for a relative wheel movement
+    fn scroll_args(dx: i32, dy: i32) -> Vec<String> {
+        vec![
+            "mousemove".to_string(),
+            "--wheel".to_string(),
+            "-x".to_string(),
+            dx.to_string(),
+            "-y".to_string(),
+            dy.to_string(),
+        ]
+    }
+
+    /// Arguments for a key event (`1` is key down, `0` is key up)
+    fn key_args(modifier: Modifier, down: bool) -> Vec<String> {
+        vec![
+            "key".to_string(),
+            format!("{}:{}", modifier.keycode(), down as u8),
+        ]
+    }
+
+    /// Run ydotool with the given arguments and wait for it to finish
+    fn run(&self, args: &[String]) -> Result<()> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .output()
+            .with_context(|| {
+                format!(
+                    "Failed to execute '{} {}'.\n\
+                     Is ydotool installed? Try: sudo pacman -S ydotool",
+                    self.program,
+                    args.join(" ")
+                )
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!(
+                "ydotool command failed with status: {}\n\
+                 stderr: {}\n\
+                 Is ydotoold running? Try: systemctl start ydotool",
+                output.status,
+                stderr
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl PointerBackend for YdotoolBackend {
+    fn move_to(&mut self, x: i32, y: i32) -> Result<()> {
+        self.run(&Self::move_args(x, y))
+    }
+
+    fn click(&mut self, button: Button) -> Result<()> {
+        self.run(&Self::button_args(button, 0xC0))
+    }
+
+    fn press(&mut self, button: Button) -> Result<()> {
+        self.run(&Self::button_args(button, 0x40))
+    }
+
+    fn release(&mut self, button: Button) -> Result<()> {
+        self.run(&Self::button_args(button, 0x80))
+    }
+
+    fn scroll(&mut self, dx: i32, dy: i32) -> Result<()> {
+        self.run(&Self::scroll_args(dx, dy))
+    }
+
+    fn key_down(&mut self, modifier: Modifier) -> Result<()> {
+        self.run(&Self::key_args(modifier, true))
+    }
+
+    fn key_up(&mut self, modifier: Modifier) -> Result<()> {
+        self.run(&Self::key_args(modifier, false))
+    }
+}
+
+/// Perform an action at the center of a cell
+///
+/// # Arguments
+/// * `backend` - The pointer backend used to synthesize input
+/// * `action` - The action chosen by the user
+/// * `target` - The cell to act on; its center is the click target
+///
+/// A drag needs a second point, so [`Action::Drag`] is performed by
+/// [`execute_drag`] instead and fails here.
+///
+/// # Returns
+/// - `Ok(())` if every step succeeded
+/// - `Err` from the first step that failed (later steps are not attempted,
+///   except that a held modifier is always released)
+pub fn execute_action(
+    backend: &mut dyn PointerBackend,
+    action: Action,
+    target: &CellCoordinates,
+) -> Result<()> {
+    let (x, y) = target.center();
+    backend.move_to(x, y)?;
+
+    match action {
+        Action::LeftClick => backend.click(Button::Left),
+        Action::RightClick => backend.click(Button::Right),
+        Action::MiddleClick => backend.click(Button::Middle),
+        Action::DoubleClick => click_times(backend, 2),
+        Action::TripleClick => click_times(backend, 3),
+        Action::ShiftClick => click_with(backend, Modifier::Shift),
+        Action::CtrlClick => click_with(backend, Modifier::Ctrl),
+        Action::AltClick => click_with(backend, Modifier::Alt),
+        Action::Move => Ok(()),
+        Action::Drag => Err(anyhow!("A drag needs an end point as well as a start point")),
+    }
+}
+
+/// Drag with the left button from the center of one cell to another
+///
+/// The button is pressed at `from`, the pointer moved to `to`, and the button
+/// released there.
+///
+/// # Returns
+/// - `Ok(())` if every step succeeded
+/// - `Err` from the first step that failed (the button is still released if
+///   it was pressed)
+pub fn execute_drag(
+    backend: &mut dyn PointerBackend,
+    from: &CellCoordinates,
+    to: &CellCoordinates,
+) -> Result<()> {
+    let (x, y) = from.center();
+    backend.move_to(x, y)?;
+    backend.press(Button::Left)?;
+
+    let (x, y) = to.center();
+    let moved = backend.move_to(x, y);
+    // Never leave the button stuck, even if the move failed
+    let released = backend.release(Button::Left);
+    moved.and(released)
+}
+
+/// Left click several times in a row
+fn click_times(backend: &mut dyn PointerBackend, count: usize) -> Result<()> {
+    for _ in 0..count {
+        backend.click(Button::Left)?;
+    }
+    Ok(())
+}
+
+/// Left click while holding a modifier key
+fn click_with(backend: &mut dyn PointerBackend, modifier: Modifier) -> Result<()> {
+    backend.key_down(modifier)?;
+    let clicked = backend.click(Button::Left);
+    // Never leave the modifier stuck, even if the click failed
+    let released = backend.key_up(modifier);
+    clicked.and(released)
+}
+
+/// Perform an action at the center of the cell with the given label
+///
+/// This is the non-interactive path used by `hyprgrid click` and
+/// `hyprgrid move`: the label is resolved against the grid without showing
+/// the overlay.
+///
+/// # Arguments
+/// * `backend` - The pointer backend used to synthesize input
+/// * `grid` - The grid of the monitor to act on
+/// * `label` - The cell label (matched case-insensitively)
+/// * `action` - The action to perform
+///
+/// # Returns
+/// - `Ok(())` if every step succeeded
+/// - `Err` if the grid has no such label, or from the first step that failed
+pub fn execute_at_label(
+    backend: &mut dyn PointerBackend,
+    grid: &Grid,
+    label: &str,
+    action: Action,
+) -> Result<()> {
+    let cell = grid.get_cell(&label.to_lowercase()).ok_or_else(|| {
+        anyhow!(
+            "No cell labeled '{}' in the {}x{} grid.\n\
+             Run 'hyprgrid dump' to list the labels.",
+            label,
+            grid.rows,
+            grid.cols
+        )
+    })?;
+    execute_action(backend, action, &cell.coordinates)
+}
+
+/// A pointer backend that records calls instead of executing them
+#[cfg(test)]
+pub mod recording {
+    use super::*;
+
+    /// A single call made against a [`RecordingBackend`]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum PointerEvent {
+        MoveTo(i32, i32),
+        Click(Button),
+        Press(Button),
+        Release(Button),
+        Scroll(i32, i32),
+        KeyDown(Modifier),
+        KeyUp(Modifier),
+    }
+
+    #[derive(Debug, Default)]
+    pub struct RecordingBackend {
+        pub events: Vec<PointerEvent>,
+    }
+
+    impl PointerBackend for RecordingBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<()> {
+            self.events.push(PointerEvent::MoveTo(x, y));
+            Ok(())
+        }
+
+        fn click(&mut self, button: Button) -> Result<()> {
+            self.events.push(PointerEvent::Click(button));
+            Ok(())
+        }
+
+        fn press(&mut self, button: Button) -> Result<()> {
+            self.events.push(PointerEvent::Press(button));
+            Ok(())
+        }
+
+        fn release(&mut self, button: Button) -> Result<()> {
+            self.events.push(PointerEvent::Release(button));
+            Ok(())
+        }
+
+        fn scroll(&mut self, dx: i32, dy: i32) -> Result<()> {
+            self.events.push(PointerEvent::Scroll(dx, dy));
+            Ok(())
+        }
+
+        fn key_down(&mut self, modifier: Modifier) -> Result<()> {
+            self.events.push(PointerEvent::KeyDown(modifier));
+            Ok(())
+        }
+
+        fn key_up(&mut self, modifier: Modifier) -> Result<()> {
+            self.events.push(PointerEvent::KeyUp(modifier));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recording::{PointerEvent, RecordingBackend};
+    use super::*;
+
+    fn cell() -> CellCoordinates {
+        CellCoordinates {
+            x: 96,
+            y: 108,
+            width: 96,
+            height: 108,
+        }
+    }
+
+    #[test]
+    fn test_ydotool_move_args() {
+        assert_eq!(
+            YdotoolBackend::move_args(960, 540),
+            vec!["mousemove", "--absolute", "-x", "960", "-y", "540"]
+        );
+    }
+
+    #[test]
+    fn test_ydotool_button_args() {
+        assert_eq!(YdotoolBackend::button_args(Button::Left, 0xC0), vec!["click", "0xC0"]);
+        assert_eq!(YdotoolBackend::button_args(Button::Right, 0xC0), vec!["click", "0xC1"]);
+        assert_eq!(YdotoolBackend::button_args(Button::Middle, 0x40), vec!["click", "0x42"]);
+        assert_eq!(YdotoolBackend::button_args(Button::Left, 0x80), vec!["click", "0x80"]);
+    }
+
+    #[test]
+    fn test_ydotool_scroll_args() {
+        assert_eq!(
+            YdotoolBackend::scroll_args(0, -3),
+            vec!["mousemove", "--wheel", "-x", "0", "-y", "-3"]
+        );
+    }
+
+    #[test]
+    fn test_ydotool_key_args() {
+        assert_eq!(YdotoolBackend::key_args(Modifier::Shift, true), vec!["key", "42:1"]);
+        assert_eq!(YdotoolBackend::key_args(Modifier::Ctrl, false), vec!["key", "29:0"]);
+        assert_eq!(YdotoolBackend::key_args(Modifier::Alt, true), vec!["key", "56:1"]);
+    }
+
+    #[test]
+    fn test_ydotool_missing_binary_reports_error() {
+        let mut backend = YdotoolBackend {
+            program: "hyprgrid-nonexistent-ydotool".to_string(),
+        };
+        let err = backend.move_to(0, 0).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to execute"));
+    }
+
+    #[test]
+    fn test_execute_left_click() {
+        let mut backend = RecordingBackend::default();
+        execute_action(&mut backend, Action::LeftClick, &cell()).unwrap();
+
+        assert_eq!(
+            backend.events,
+            vec![PointerEvent::MoveTo(144, 162), PointerEvent::Click(Button::Left)]
+        );
+    }
+
+    #[test]
+    fn test_execute_uses_global_coordinates() {
+        let target = CellCoordinates {
+            x: -1920,
+            y: 0,
+            width: 96,
+            height: 108,
+        };
+        let mut backend = RecordingBackend::default();
+        execute_action(&mut backend, Action::LeftClick, &target).unwrap();
+
+        assert_eq!(backend.events[0], PointerEvent::MoveTo(-1872, 54));
+    }
+
+    #[test]
+    fn test_execute_every_action() {
+        use PointerEvent::{Click, KeyDown, KeyUp};
+
+        let cases = [
+            (Action::LeftClick, vec![Click(Button::Left)]),
+            (Action::RightClick, vec![Click(Button::Right)]),
+            (Action::MiddleClick, vec![Click(Button::Middle)]),
+            (Action::DoubleClick, vec![Click(Button::Left); 2]),
+            (Action::TripleClick, vec![Click(Button::Left); 3]),
+            (Action::ShiftClick, vec![KeyDown(Modifier::Shift), Click(Button::Left), KeyUp(Modifier::Shift)]),
+            (Action::CtrlClick, vec![KeyDown(Modifier::Ctrl), Click(Button::Left), KeyUp(Modifier::Ctrl)]),
+            (Action::AltClick, vec![KeyDown(Modifier::Alt), Click(Button::Left), KeyUp(Modifier::Alt)]),
+            (Action::Move, vec![]),
+        ];
+        // Action::Drag needs two points; see test_execute_drag
+
+        for (action, clicks) in cases {
+            let mut backend = RecordingBackend::default();
+            execute_action(&mut backend, action, &cell()).unwrap();
+
+            let mut expected = vec![PointerEvent::MoveTo(144, 162)];
+            expected.extend(clicks);
+            assert_eq!(backend.events, expected, "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn test_execute_drag() {
+        let to = CellCoordinates {
+            x: -1920,
+            y: 0,
+            width: 96,
+            height: 108,
+        };
+        let mut backend = RecordingBackend::default();
+        execute_drag(&mut backend, &cell(), &to).unwrap();
+
+        assert_eq!(
+            backend.events,
+            vec![
+                PointerEvent::MoveTo(144, 162),
+                PointerEvent::Press(Button::Left),
+                PointerEvent::MoveTo(-1872, 54),
+                PointerEvent::Release(Button::Left),
+            ]
+        );
+
+        let mut backend = RecordingBackend::default();
+        assert!(execute_action(&mut backend, Action::Drag, &cell()).is_err());
+    }
+
+    #[test]
+    fn test_modifier_released_after_failed_click() {
+        struct FailingClick(RecordingBackend);
+
+        impl PointerBackend for FailingClick {
+            fn move_to(&mut self, x: i32, y: i32) -> Result<()> {
+                self.0.move_to(x, y)
+            }
+            fn click(&mut self, _button: Button) -> Result<()> {
+                Err(anyhow!("click failed"))
+            }
+            fn press(&mut self, button: Button) -> Result<()> {
+                self.0.press(button)
+            }
+            fn release(&mut self, button: Button) -> Result<()> {
+                self.0.release(button)
+            }
+            fn scroll(&mut self, dx: i32, dy: i32) -> Result<()> {
+                self.0.scroll(dx, dy)
+            }
+            fn key_down(&mut self, modifier: Modifier) -> Result<()> {
+                self.0.key_down(modifier)
+            }
+            fn key_up(&mut self, modifier: Modifier) -> Result<()> {
+                self.0.key_up(modifier)
+            }
+        }
+
+        let mut backend = FailingClick(RecordingBackend::default());
+        let err = execute_action(&mut backend, Action::CtrlClick, &cell()).unwrap_err();
+        assert_eq!(err.to_string(), "click failed");
+        assert_eq!(backend.0.events.last(), Some(&PointerEvent::KeyUp(Modifier::Ctrl)));
+    }
+
+    #[test]
+    fn test_execute_move() {
+        let mut backend = RecordingBackend::default();
+        execute_action(&mut backend, Action::Move, &cell()).unwrap();
+
+        assert_eq!(backend.events, vec![PointerEvent::MoveTo(144, 162)]);
+    }
+
+    #[test]
+    fn test_execute_at_label() {
+        let grid = Grid::new(2, 3, 600, 400).unwrap();
+        let label = grid.cell_at(1, 2).unwrap().label.clone();
+
+        let mut backend = RecordingBackend::default();
+        execute_at_label(&mut backend, &grid, &label.to_uppercase(), Action::RightClick).unwrap();
+        assert_eq!(
+            backend.events,
+            vec![PointerEvent::MoveTo(500, 300), PointerEvent::Click(Button::Right)]
+        );
+
+        let mut backend = RecordingBackend::default();
+        let err = execute_at_label(&mut backend, &grid, "zz", Action::Move).unwrap_err();
+        assert!(err.to_string().contains("No cell labeled 'zz' in the 2x3 grid"));
+        assert!(backend.events.is_empty());
+    }
+
+    #[test]
+    fn test_execute_right_click() {
+        let mut backend = RecordingBackend::default();
+        execute_action(&mut backend, Action::RightClick, &cell()).unwrap();
+
+        assert_eq!(
+            backend.events,
+            vec![PointerEvent::MoveTo(144, 162), PointerEvent::Click(Button::Right)]
+        );
+    }
+}